@@ -83,7 +83,7 @@ export class PredictionMarketClient {
       );
 
       const signature = await this.program.methods
-        .createMarket(marketId, question, outcomes, endTime, oracle, minBet)
+        .createMarket(marketId, question, outcomes, endTime, oracle, minBet, new anchor.BN(0), 0, new anchor.BN(0), "", { other: {} }, [], Array(32).fill(0))
         .accounts({
           market,
           marketVault,
@@ -138,6 +138,7 @@ export class PredictionMarketClient {
           userBet,
           marketVault,
           userTokenAccount,
+          globalState: this.globalState,
           user,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
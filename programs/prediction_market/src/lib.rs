@@ -1,15 +1,72 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint};
+use anchor_lang::solana_program::{
+    ed25519_program,
+    hash::hashv,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+    program_option::COption,
+    sysvar::{instructions as ix_sysvar, slot_hashes},
+};
+use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Burn, CloseAccount, Token, TokenAccount, Transfer, Mint};
 
 declare_id!("11111111111111111111111111111112");
 
+/// Metaplex Token Metadata - gives receipt mints the metadata wallets display
+mod token_metadata {
+    use super::*;
+    declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+// Defaults for GlobalState.limits - adjustable via update_limits
+const DEFAULT_MAX_OUTCOMES: u8 = 8;          // Reasonable limit
+const DEFAULT_MAX_QUESTION_LEN: u16 = 200;   // Twitter-like limit
+const DEFAULT_MAX_OUTCOME_LEN: u16 = 50;     // Short and clear
+const DEFAULT_MAX_FEE_BPS: u16 = 500;        // 5% max fee (reasonable)
+const DEFAULT_MIN_DURATION: i64 = 3600;      // 1 hour minimum
+const DEFAULT_MAX_DURATION: i64 = 7776000;   // 90 days maximum
+
+// Absolute bounds the authority can never configure past
+const ABS_MAX_OUTCOMES: u8 = 16;
+const ABS_MAX_QUESTION_LEN: u16 = 500;
+const ABS_MAX_OUTCOME_LEN: u16 = 100;
+const ABS_MAX_FEE_BPS: u16 = 1000;          // 10%
+const ABS_MIN_DURATION: i64 = 300;          // 5 minutes
+const ABS_MAX_DURATION: i64 = 63072000;     // 2 years
+const ABS_MIN_DISPUTE_WINDOW: i64 = 60;     // 1 minute
+const ABS_MAX_DISPUTE_WINDOW: i64 = 259200; // 3 days - well inside the oracle unstake cooldown
+
 // Practical constants - not over-engineered
-const MAX_OUTCOMES: usize = 8;           // Reasonable limit
-const MAX_QUESTION_LEN: usize = 200;     // Twitter-like limit
-const MAX_OUTCOME_LEN: usize = 50;       // Short and clear
-const MAX_FEE_BPS: u16 = 500;           // 5% max fee (reasonable)
-const MIN_DURATION: i64 = 3600;         // 1 hour minimum
-const MAX_DURATION: i64 = 7776000;      // 90 days maximum
+const MAX_URI_LEN: usize = 200;         // Off-chain metadata JSON
+const MAX_TAGS: usize = 5;              // Enough for search and filters
+const MAX_TAG_LEN: usize = 20;          // Single words, not sentences
+const MAX_RESOLVER_FEE_BPS: u16 = 500;  // 5% max to whoever resolves
+const DISPUTE_WINDOW: i64 = 86400;      // Default 24 hours to challenge a resolution
+const PANEL_SIZE: usize = 5;            // Jurors drawn per dispute
+const MAX_JURORS: usize = 64;           // Registry capacity
+const MAX_SLASH_BPS: u16 = 5000;        // Jurors never lose more than half
+const ORACLE_SLASH_BPS: u16 = 1000;     // 10% of oracle stake per lost dispute
+const ORACLE_UNSTAKE_COOLDOWN: i64 = 604800; // 7 days - outlasts any dispute
+const MAX_PERMIT_MINTS: usize = 4;      // Per-creator mint allowlist
+const TIMELOCK_DELAY: i64 = 172800;     // 48 hours notice for sensitive changes
+const MAX_LARGE_OUTCOMES: usize = 64;   // Elections and tournaments
+const NO_WINNER: u8 = u8::MAX;          // LargeMarket.winner for closed markets
+const ACCOUNT_VERSION: u8 = 1;          // Bump with every account layout change
+const MAX_EVENT_MARKETS: usize = 16;    // Props for one match, one card
+const MAX_GROUP_MARKETS: usize = 16;    // One binary market per candidate
+const MAX_DELEGATE_MARKETS: usize = 8;  // Markets a session key may bet on
+const YES: usize = 0;                   // Outcome indexes of grouped binary markets
+const NO: usize = 1;
+const MAX_RECEIPT_NAME_LEN: usize = 32; // Metaplex name limit
+const RECEIPT_SYMBOL: &str = "BET";
+
+// Circuit breaker flags for GlobalState.paused_ops
+const PAUSE_BETS: u8 = 1 << 0;
+const PAUSE_CREATION: u8 = 1 << 1;
+const PAUSE_CLAIMS: u8 = 1 << 2;        // Every payout out of a program vault
+const PAUSE_RESOLUTION: u8 = 1 << 3;
+const PAUSE_ALL: u8 = PAUSE_BETS | PAUSE_CREATION | PAUSE_CLAIMS | PAUSE_RESOLUTION;
 
 #[program]
 pub mod prediction_market {
@@ -21,7 +78,7 @@ pub mod prediction_market {
         fee_bps: u16,
         fee_recipient: Pubkey,
     ) -> Result<()> {
-        require!(fee_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh);
+        require!(fee_bps <= DEFAULT_MAX_FEE_BPS, ErrorCode::FeeTooHigh);
         require!(fee_recipient != Pubkey::default(), ErrorCode::InvalidFeeRecipient);
         
         let state = &mut ctx.accounts.global_state;
@@ -30,6 +87,15 @@ pub mod prediction_market {
         state.fee_recipient = fee_recipient;
         state.total_markets = 0;
         state.bump = ctx.bumps.global_state;
+        state.creation_bond = 0;
+        state.permissioned_creation = false;
+        state.pending_authority = Pubkey::default();
+        state.change_nonce = 0;
+        state.paused_ops = 0;
+        state.limits = PlatformLimits::default();
+        state.version = ACCOUNT_VERSION;
+        state.dispute_window = DISPUTE_WINDOW;
+        state.reserved = [0; 56];
 
         emit!(PlatformInitialized {
             authority: state.authority,
@@ -40,6 +106,7 @@ pub mod prediction_market {
     }
 
     /// Create market - flexible but validated
+    #[allow(clippy::too_many_arguments)]
     pub fn create_market(
         ctx: Context<CreateMarket>,
         market_id: u64,
@@ -48,49 +115,109 @@ pub mod prediction_market {
         end_time: i64,
         oracle: Pubkey,
         min_bet: u64,
+        min_oracle_stake: u64,
+        resolver_fee_bps: u16,
+        resolver_bounty: u64,
+        metadata_uri: String,
+        category: MarketCategory,
+        tags: Vec<String>,
+        rules_hash: [u8; 32],
     ) -> Result<()> {
-        let now = Clock::get()?.unix_timestamp;
-        
-        // Smart validation - not excessive
-        require!(outcomes.len() >= 2 && outcomes.len() <= MAX_OUTCOMES, ErrorCode::InvalidOutcomes);
-        require!(end_time > now && end_time - now >= MIN_DURATION, ErrorCode::InvalidEndTime);
-        require!(end_time - now <= MAX_DURATION, ErrorCode::EndTimeTooFar);
-        require!(!question.trim().is_empty() && question.len() <= MAX_QUESTION_LEN, ErrorCode::InvalidQuestion);
-        require!(min_bet > 0, ErrorCode::InvalidMinBet);
-        
         // Market ID uniqueness is enforced by PDA seeds - if market_id exists, init will fail
-        // This provides automatic uniqueness validation
+        let bump = ctx.bumps.market;
+        init_market(
+            ctx.accounts.new_market(),
+            market_id,
+            bump,
+            MarketParams {
+                question,
+                outcomes,
+                end_time,
+                oracle,
+                min_bet,
+                min_oracle_stake,
+                resolver_fee_bps,
+                resolver_bounty,
+                metadata_uri,
+                category,
+                tags,
+                rules_hash,
+            },
+        )
+    }
 
-        // Validate outcomes - practical checks
-        for outcome in &outcomes {
-            require!(!outcome.trim().is_empty() && outcome.len() <= MAX_OUTCOME_LEN, ErrorCode::InvalidOutcome);
+    /// Create market with the creator's next sequential ID - returned via return data
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_market_auto(
+        ctx: Context<CreateMarketAuto>,
+        question: String,
+        outcomes: Vec<String>,
+        end_time: i64,
+        oracle: Pubkey,
+        min_bet: u64,
+        min_oracle_stake: u64,
+        resolver_fee_bps: u16,
+        resolver_bounty: u64,
+        metadata_uri: String,
+        category: MarketCategory,
+        tags: Vec<String>,
+        rules_hash: [u8; 32],
+    ) -> Result<u64> {
+        let counter = &mut ctx.accounts.creator_counter;
+        if counter.creator == Pubkey::default() {
+            counter.creator = ctx.accounts.creator.key();
+            counter.bump = ctx.bumps.creator_counter;
+            counter.version = ACCOUNT_VERSION;
+            counter.reserved = [0; 32];
         }
+        let market_id = counter.next_id;
+        counter.next_id = market_id.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+        let bump = ctx.bumps.market;
+        init_market(
+            ctx.accounts.new_market(),
+            market_id,
+            bump,
+            MarketParams {
+                question,
+                outcomes,
+                end_time,
+                oracle,
+                min_bet,
+                min_oracle_stake,
+                resolver_fee_bps,
+                resolver_bounty,
+                metadata_uri,
+                category,
+                tags,
+                rules_hash,
+            },
+        )?;
+
+        Ok(market_id)
+    }
 
+    /// Update off-chain metadata and tags - creator only, resizes the account
+    pub fn update_market_metadata(
+        ctx: Context<UpdateMarketMetadata>,
+        metadata_uri: String,
+        category: MarketCategory,
+        tags: Vec<String>,
+    ) -> Result<()> {
         let market = &mut ctx.accounts.market;
-        market.id = market_id;
-        market.creator = ctx.accounts.creator.key();
-        market.question = question.clone();
-        market.outcomes = outcomes.clone();
-        market.end_time = end_time;
-        market.oracle = oracle;
-        market.min_bet = min_bet;
-        market.total_pool = 0;
-        market.outcome_pools = vec![0; outcomes.len()];
-        market.resolved = false;
-        market.winner = None;
-        market.paused = false;
-        market.created_at = now;
-        market.bump = ctx.bumps.market;
 
-        // Update global counter
-        ctx.accounts.global_state.total_markets += 1;
+        require!(!market.resolved, ErrorCode::MarketResolved);
+        validate_metadata(&metadata_uri, &tags)?;
 
-        emit!(MarketCreated {
-            market_id,
-            question,
-            outcomes,
-            end_time,
-            oracle,
+        market.metadata_uri = metadata_uri.clone();
+        market.category = category;
+        market.tags = tags.clone();
+
+        emit!(MarketMetadataUpdated {
+            market: market.key(),
+            metadata_uri,
+            category,
+            tags,
         });
 
         Ok(())
@@ -106,16 +233,10 @@ pub mod prediction_market {
         let now = Clock::get()?.unix_timestamp;
 
         // Essential validations
-        require!(!market.resolved, ErrorCode::MarketResolved);
-        require!(!market.paused, ErrorCode::MarketPaused);
-        require!(now < market.end_time, ErrorCode::MarketExpired);
-        require!(outcome_index < market.outcomes.len() as u8, ErrorCode::InvalidOutcome);
-        require!(amount >= market.min_bet, ErrorCode::BetTooSmall);
+        require_not_paused(&ctx.accounts.global_state, PAUSE_BETS)?;
+        check_bet(market, outcome_index, amount, now)?;
 
-        // Safe arithmetic
-        market.total_pool = market.total_pool.checked_add(amount).ok_or(ErrorCode::Overflow)?;
-        market.outcome_pools[outcome_index as usize] = 
-            market.outcome_pools[outcome_index as usize].checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        add_to_pools(market, outcome_index, amount)?;
 
         // Transfer tokens
         token::transfer(
@@ -131,22 +252,18 @@ pub mod prediction_market {
         )?;
 
         // Update user bet
-        let user_bet = &mut ctx.accounts.user_bet;
-        if user_bet.user == Pubkey::default() {
-            user_bet.user = ctx.accounts.user.key();
-            user_bet.market = market.key();
-            user_bet.bets = vec![0; market.outcomes.len()];
-            user_bet.total_bet = 0;
-            user_bet.claimed = false;
-            user_bet.bump = ctx.bumps.user_bet;
-        }
-
-        user_bet.bets[outcome_index as usize] = 
-            user_bet.bets[outcome_index as usize].checked_add(amount).ok_or(ErrorCode::Overflow)?;
-        user_bet.total_bet = user_bet.total_bet.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        credit_user_bet(
+            &mut ctx.accounts.user_bet,
+            market,
+            ctx.accounts.user.key(),
+            ctx.bumps.user_bet,
+            outcome_index,
+            amount,
+        )?;
 
         emit!(BetPlaced {
             user: ctx.accounts.user.key(),
+            market: market.key(),
             market_id: market.id,
             outcome_index,
             amount,
@@ -155,6 +272,59 @@ pub mod prediction_market {
         Ok(())
     }
 
+    /// Bet on several outcomes at once - the minimum bet applies to the combined stake
+    pub fn place_bets(ctx: Context<PlaceBet>, legs: Vec<BetLeg>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let now = Clock::get()?.unix_timestamp;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_BETS)?;
+        require!(!legs.is_empty() && legs.len() <= market.outcomes.len(), ErrorCode::InvalidLegs);
+
+        let mut total = 0u64;
+        for leg in &legs {
+            require!(leg.amount > 0, ErrorCode::InvalidAmount);
+            total = total.checked_add(leg.amount).ok_or(ErrorCode::Overflow)?;
+        }
+        for leg in &legs {
+            check_bet(market, leg.outcome_index, total, now)?;
+        }
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.market_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                }
+            ),
+            total
+        )?;
+
+        let user = ctx.accounts.user.key();
+        for leg in &legs {
+            add_to_pools(market, leg.outcome_index, leg.amount)?;
+            credit_user_bet(
+                &mut ctx.accounts.user_bet,
+                market,
+                user,
+                ctx.bumps.user_bet,
+                leg.outcome_index,
+                leg.amount,
+            )?;
+
+            emit!(BetPlaced {
+                user,
+                market: market.key(),
+                market_id: market.id,
+                outcome_index: leg.outcome_index,
+                amount: leg.amount,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Resolve market - oracle or authority
     pub fn resolve_market(
         ctx: Context<ResolveMarket>,
@@ -163,23 +333,39 @@ pub mod prediction_market {
         let market = &mut ctx.accounts.market;
         let now = Clock::get()?.unix_timestamp;
 
-        // Authority check
+        require_not_paused(&ctx.accounts.global_state, PAUSE_RESOLUTION)?;
+
+        // Oracle, or a resolver of last resort
         require!(
-            ctx.accounts.resolver.key() == market.oracle || 
-            ctx.accounts.resolver.key() == ctx.accounts.global_state.authority,
+            ctx.accounts.resolver.key() == market.oracle ||
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.resolver.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::Resolver,
+            ),
             ErrorCode::Unauthorized
         );
 
         // State validations
+        require!(!market.in_group, ErrorCode::MarketInGroup);
         require!(!market.resolved, ErrorCode::AlreadyResolved);
         require!(now >= market.end_time, ErrorCode::TooEarly);
         require!(winning_outcome < market.outcomes.len() as u8, ErrorCode::InvalidOutcome);
         require!(market.outcome_pools[winning_outcome as usize] > 0, ErrorCode::NoWinners);
 
+        if ctx.accounts.resolver.key() == market.oracle {
+            record_oracle_resolution(market, ctx.accounts.oracle_profile.as_mut(), now)?;
+        }
+
         market.resolved = true;
         market.winner = Some(winning_outcome);
+        market.resolved_at = now;
+        market.resolved_by = ctx.accounts.resolver.key();
+        release_permit_slot(market, ctx.accounts.creator_permit.as_mut())?;
 
         emit!(MarketResolved {
+            market: market.key(),
             market_id: market.id,
             winner: winning_outcome,
         });
@@ -191,44 +377,19 @@ pub mod prediction_market {
     pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
         let market = &ctx.accounts.market;
         let user_bet = &mut ctx.accounts.user_bet;
-        let global_state = &ctx.accounts.global_state;
 
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
         require!(market.resolved, ErrorCode::NotResolved);
-        require!(!user_bet.claimed, ErrorCode::AlreadyClaimed);
-        require!(user_bet.user == ctx.accounts.user.key(), ErrorCode::Unauthorized);
-
         // Handle case where market was closed (no winner)
-        let winner = market.winner.ok_or(ErrorCode::MarketClosed)?;
-        let user_winning_bet = user_bet.bets[winner as usize];
-        require!(user_winning_bet > 0, ErrorCode::NoWinningBet);
+        require!(market.winner.is_some(), ErrorCode::MarketClosed);
 
-        // Calculate payout - clean math with overflow protection
-        let total_pool = market.total_pool;
-        let winning_pool = market.outcome_pools[winner as usize];
-        let platform_fee = total_pool * global_state.fee_bps as u64 / 10000;
-        
-        // Ensure platform fee doesn't exceed total pool (safety check)
-        require!(platform_fee < total_pool, ErrorCode::FeeExceedsPool);
-        
-        let prize_pool = total_pool - platform_fee;
-        
-        // Use checked arithmetic and ensure no precision loss
-        let user_winnings = (user_winning_bet as u128)
-            .checked_mul(prize_pool as u128)
-            .ok_or(ErrorCode::Overflow)?
-            .checked_div(winning_pool as u128)
-            .ok_or(ErrorCode::DivisionByZero)?
-            as u64;
-            
-
-            
-
-        require!(user_winnings >= user_winning_bet, ErrorCode::InvalidPayout);
+        let user_winnings = claim_payout(market, user_bet, Clock::get()?.unix_timestamp)?;
 
         // Transfer winnings
+        let market_key = market.key();
         let seeds = &[
             b"vault",
-            market.key().as_ref(),
+            market_key.as_ref(),
             &[ctx.bumps.market_vault],
         ];
         
@@ -256,22 +417,101 @@ pub mod prediction_market {
         Ok(())
     }
 
+    /// Claim winnings and refunds from many markets into one token account
+    pub fn claim_many<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimMany<'info>>) -> Result<()> {
+        let user = ctx.accounts.user.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
+        // (market, user_bet, market_vault) per market
+        require!(
+            !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.chunks_exact(3).remainder().is_empty(),
+            ErrorCode::InvalidClaimAccounts
+        );
+
+        for accounts in ctx.remaining_accounts.chunks(3) {
+            let market = Account::<Market>::try_from(&accounts[0])?;
+            let mut user_bet = Account::<UserBet>::try_from(&accounts[1])?;
+            let market_vault = Account::<TokenAccount>::try_from(&accounts[2])?;
+            let market_key = market.key();
+
+            let (bet_address, _) = Pubkey::find_program_address(&[b"bet", user.as_ref(), market_key.as_ref()], &crate::ID);
+            require_keys_eq!(user_bet.key(), bet_address, ErrorCode::InvalidUserBet);
+            require_keys_eq!(user_bet.user, user, ErrorCode::Unauthorized);
+            let (vault_address, vault_bump) = Pubkey::find_program_address(&[b"vault", market_key.as_ref()], &crate::ID);
+            require_keys_eq!(market_vault.key(), vault_address, ErrorCode::InvalidVault);
+
+            let payout = claim_payout(&market, &user_bet, now)?;
+
+            let seeds = &[
+                b"vault",
+                market_key.as_ref(),
+                &[vault_bump],
+            ];
+
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: market_vault.to_account_info(),
+                        to: ctx.accounts.user_token_account.to_account_info(),
+                        authority: market_vault.to_account_info(),
+                    },
+                    &[&seeds[..]]
+                ),
+                payout
+            )?;
+
+            // Written back before the next triple so a repeated bet reads as claimed
+            user_bet.claimed = true;
+            user_bet.exit(&crate::ID)?;
+
+            if market.winner.is_some() {
+                emit!(WinningsClaimed {
+                    user,
+                    market_id: market.id,
+                    amount: payout,
+                });
+            } else {
+                emit!(RefundClaimed {
+                    user,
+                    market_id: market.id,
+                    amount: payout,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Collect platform fees
     pub fn collect_fees(ctx: Context<CollectFees>) -> Result<()> {
-        let market = &ctx.accounts.market;
+        let market = &mut ctx.accounts.market;
         let global_state = &ctx.accounts.global_state;
 
-        require!(ctx.accounts.authority.key() == global_state.authority, ErrorCode::Unauthorized);
+        require_not_paused(global_state, PAUSE_CLAIMS)?;
+        require!(
+            has_role(global_state, &ctx.accounts.authority.key(), ctx.accounts.role_grant.as_ref(), Role::FeeManager),
+            ErrorCode::Unauthorized
+        );
         require!(market.resolved, ErrorCode::NotResolved);
+        require!(market.winner.is_some(), ErrorCode::MarketClosed);
+        require!(!market.disputed, ErrorCode::MarketDisputed);
+        require!(!market.fees_collected, ErrorCode::FeesAlreadyCollected);
+        // A dispute can still turn this into a refund market until the window closes
+        require!(
+            Clock::get()?.unix_timestamp >= dispute_deadline(market),
+            ErrorCode::DisputeWindowOpen
+        );
 
-        let platform_fee = market.total_pool * global_state.fee_bps as u64 / 10000;
-        
-        require!(platform_fee < market.total_pool, ErrorCode::FeeExceedsPool);
+        let (platform_fee, _) = settlement_fees(market)?;
+        market.fees_collected = true;
         
         if platform_fee > 0 {
+            let market_key = market.key();
             let seeds = &[
                 b"vault",
-                market.key().as_ref(),
+                market_key.as_ref(),
                 &[ctx.bumps.market_vault],
             ];
             
@@ -305,11 +545,20 @@ pub mod prediction_market {
         let market = &mut ctx.accounts.market;
         
         require!(
-            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.authority.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::MarketModerator,
+            ),
             ErrorCode::Unauthorized
         );
         require!(!market.resolved, ErrorCode::AlreadyResolved);
         require!(new_oracle != Pubkey::default(), ErrorCode::InvalidOracle);
+        consume_change(
+            &ctx.accounts.pending_change,
+            &ConfigChange::Oracle { market: market.key(), new_oracle },
+        )?;
 
         let old_oracle = market.oracle;
         market.oracle = new_oracle;
@@ -328,7 +577,12 @@ pub mod prediction_market {
         let market = &mut ctx.accounts.market;
         
         require!(
-            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.authority.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::Pauser,
+            ),
             ErrorCode::Unauthorized
         );
         require!(!market.resolved, ErrorCode::AlreadyResolved);
@@ -348,7 +602,12 @@ pub mod prediction_market {
         let market = &mut ctx.accounts.market;
         
         require!(
-            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.authority.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::Pauser,
+            ),
             ErrorCode::Unauthorized
         );
         require!(!market.resolved, ErrorCode::AlreadyResolved);
@@ -368,15 +627,25 @@ pub mod prediction_market {
         let market = &mut ctx.accounts.market;
         
         require!(
-            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.authority.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::MarketModerator,
+            ),
             ErrorCode::Unauthorized
         );
+        require!(!market.in_group, ErrorCode::MarketInGroup);
         require!(!market.resolved, ErrorCode::AlreadyResolved);
 
         market.resolved = true;
         market.winner = None; // No winner for closed market
+        market.resolved_at = Clock::get()?.unix_timestamp;
+        market.bond_forfeited = true; // Closed questions lose their creation bond
+        release_permit_slot(market, ctx.accounts.creator_permit.as_mut())?;
 
         emit!(MarketClosed {
+            market: market.key(),
             market_id: market.id,
         });
 
@@ -388,18 +657,17 @@ pub mod prediction_market {
         let market = &ctx.accounts.market;
         let user_bet = &mut ctx.accounts.user_bet;
 
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
         require!(market.resolved, ErrorCode::NotResolved);
         require!(market.winner.is_none(), ErrorCode::MarketNotClosed);
-        require!(!user_bet.claimed, ErrorCode::AlreadyClaimed);
-        require!(user_bet.user == ctx.accounts.user.key(), ErrorCode::Unauthorized);
-        require!(user_bet.total_bet > 0, ErrorCode::NoRefundAvailable);
 
-        let refund_amount = user_bet.total_bet;
+        let refund_amount = claim_payout(market, user_bet, Clock::get()?.unix_timestamp)?;
 
         // Transfer refund
+        let market_key = market.key();
         let seeds = &[
             b"vault",
-            market.key().as_ref(),
+            market_key.as_ref(),
             &[ctx.bumps.market_vault],
         ];
         
@@ -426,192 +694,5293 @@ pub mod prediction_market {
 
         Ok(())
     }
-}
 
-// Clean, efficient account structures
-#[account]
-pub struct GlobalState {
-    pub authority: Pubkey,      // 32
-    pub fee_bps: u16,          // 2
-    pub fee_recipient: Pubkey,  // 32
-    pub total_markets: u64,     // 8
-    pub bump: u8,              // 1
-}
+    /// Move some or all of a position to another wallet, one amount per outcome
+    pub fn transfer_position(ctx: Context<TransferPosition>, amounts: Vec<u64>) -> Result<()> {
+        let from_bet = &mut ctx.accounts.from_bet;
+        let to_bet = &mut ctx.accounts.to_bet;
+        let recipient = ctx.accounts.recipient.key();
 
-#[account]
-pub struct Market {
-    pub id: u64,                    // 8
-    pub creator: Pubkey,            // 32
-    pub question: String,           // 4 + 200
-    pub outcomes: Vec<String>,      // 4 + (4 + 50) * 8 = 436
-    pub end_time: i64,             // 8
-    pub oracle: Pubkey,            // 32
-    pub min_bet: u64,              // 8
-    pub total_pool: u64,           // 8
-    pub outcome_pools: Vec<u64>,   // 4 + 8 * 8 = 68
-    pub resolved: bool,            // 1
-    pub winner: Option<u8>,        // 1 + 1
-    pub paused: bool,              // 1
-    pub created_at: i64,           // 8
-    pub bump: u8,                  // 1
-}
+        require_not_paused(&ctx.accounts.global_state, PAUSE_BETS)?;
+        require!(recipient != ctx.accounts.user.key(), ErrorCode::InvalidRecipient);
+        require!(amounts.len() == from_bet.bets.len(), ErrorCode::InvalidAmount);
+        require!(!from_bet.claimed && !to_bet.claimed, ErrorCode::AlreadyClaimed);
 
-#[account]
-pub struct UserBet {
-    pub user: Pubkey,        // 32
-    pub market: Pubkey,      // 32
-    pub bets: Vec<u64>,      // 4 + 8 * 8 = 68
-    pub total_bet: u64,      // 8
-    pub claimed: bool,       // 1
-    pub bump: u8,           // 1
-}
+        if to_bet.user == Pubkey::default() {
+            to_bet.user = recipient;
+            to_bet.market = from_bet.market;
+            to_bet.bets = vec![0; from_bet.bets.len()];
+            to_bet.total_bet = 0;
+            to_bet.claimed = false;
+            to_bet.bump = ctx.bumps.to_bet;
+            to_bet.version = ACCOUNT_VERSION;
+            to_bet.open_offers = 0;
+            to_bet.reserved = [0; 30];
+        }
 
-// Account contexts - practical constraints
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + 75,
-        seeds = [b"global"],
-        bump
-    )]
-    pub global_state: Account<'info, GlobalState>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+        let mut total = 0u64;
+        for (index, amount) in amounts.iter().enumerate() {
+            from_bet.bets[index] = from_bet.bets[index].checked_sub(*amount).ok_or(ErrorCode::InsufficientPosition)?;
+            to_bet.bets[index] = to_bet.bets[index].checked_add(*amount).ok_or(ErrorCode::Overflow)?;
+            total = total.checked_add(*amount).ok_or(ErrorCode::Overflow)?;
+        }
+        require!(total > 0, ErrorCode::InvalidAmount);
 
-#[derive(Accounts)]
-#[instruction(market_id: u64)]
-pub struct CreateMarket<'info> {
-    #[account(
-        init,
-        payer = creator,
-        space = 8 + 816,  // Added 1 byte for paused field
-        seeds = [b"market", market_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub market: Account<'info, Market>,
-    #[account(
-        init,
-        payer = creator,
-        seeds = [b"vault", market.key().as_ref()],
-        bump,
-        token::mint = mint,
-        token::authority = market_vault,
-    )]
-    pub market_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub global_state: Account<'info, GlobalState>,
-    pub mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
+        from_bet.total_bet -= total;
+        to_bet.total_bet = to_bet.total_bet.checked_add(total).ok_or(ErrorCode::Overflow)?;
 
-#[derive(Accounts)]
-pub struct PlaceBet<'info> {
-    #[account(mut)]
-    pub market: Account<'info, Market>,
-    #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + 142,
-        seeds = [b"bet", user.key().as_ref(), market.key().as_ref()],
-        bump
-    )]
-    pub user_bet: Account<'info, UserBet>,
-    #[account(mut)]
-    pub market_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
+        emit!(PositionTransferred {
+            market: from_bet.market,
+            from: from_bet.user,
+            to: recipient,
+            amount: total,
+        });
 
-#[derive(Accounts)]
-pub struct ResolveMarket<'info> {
-    #[account(mut)]
-    pub market: Account<'info, Market>,
-    pub global_state: Account<'info, GlobalState>,
-    pub resolver: Signer<'info>,
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct ClaimWinnings<'info> {
-    pub market: Account<'info, Market>,
-    #[account(mut)]
-    pub user_bet: Account<'info, UserBet>,
-    #[account(mut)]
-    pub market_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    pub global_state: Account<'info, GlobalState>,
-    pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-}
+    /// List part of a position for sale at a fixed price - the stake is escrowed in the offer
+    pub fn create_offer(
+        ctx: Context<CreateOffer>,
+        offer_id: u64,
+        outcome_index: u8,
+        amount: u64,
+        price: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        let seller_bet = &mut ctx.accounts.seller_bet;
 
-#[derive(Accounts)]
-pub struct CollectFees<'info> {
-    pub market: Account<'info, Market>,
-    #[account(mut)]
+        require_not_paused(&ctx.accounts.global_state, PAUSE_BETS)?;
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            expires_at > Clock::get()?.unix_timestamp && expires_at <= ctx.accounts.market.end_time,
+            ErrorCode::InvalidDuration
+        );
+        require!(!seller_bet.claimed, ErrorCode::AlreadyClaimed);
+        require!((outcome_index as usize) < seller_bet.bets.len(), ErrorCode::InvalidOutcome);
+
+        let index = outcome_index as usize;
+        seller_bet.bets[index] = seller_bet.bets[index].checked_sub(amount).ok_or(ErrorCode::InsufficientPosition)?;
+        seller_bet.total_bet -= amount;
+        seller_bet.open_offers = seller_bet.open_offers.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+        let offer = &mut ctx.accounts.offer;
+        offer.seller = ctx.accounts.seller.key();
+        offer.market = ctx.accounts.market.key();
+        offer.mint = ctx.accounts.market_vault.mint;
+        offer.offer_id = offer_id;
+        offer.outcome_index = outcome_index;
+        offer.amount = amount;
+        offer.price = price;
+        offer.expires_at = expires_at;
+        offer.bump = ctx.bumps.offer;
+        offer.version = ACCOUNT_VERSION;
+        offer.reserved = [0; 24];
+
+        emit!(OfferCreated {
+            offer: offer.key(),
+            market: offer.market,
+            seller: offer.seller,
+            outcome_index,
+            amount,
+            price,
+            expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Buy the whole offer - the position moves to the buyer and the price to the seller
+    pub fn fill_offer(ctx: Context<FillOffer>) -> Result<()> {
+        let offer = &ctx.accounts.offer;
+        let buyer = ctx.accounts.buyer.key();
+        let buyer_bet = &mut ctx.accounts.buyer_bet;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_BETS)?;
+        require!(buyer != offer.seller, ErrorCode::InvalidRecipient);
+        require!(!ctx.accounts.market.resolved, ErrorCode::MarketResolved);
+        require!(Clock::get()?.unix_timestamp < offer.expires_at, ErrorCode::OfferExpired);
+        require!(!buyer_bet.claimed, ErrorCode::AlreadyClaimed);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.buyer_token_account.to_account_info(),
+                    to: ctx.accounts.seller_token_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                }
+            ),
+            offer.price
+        )?;
+
+        if buyer_bet.user == Pubkey::default() {
+            buyer_bet.user = buyer;
+            buyer_bet.market = offer.market;
+            buyer_bet.bets = vec![0; ctx.accounts.market.outcomes.len()];
+            buyer_bet.total_bet = 0;
+            buyer_bet.claimed = false;
+            buyer_bet.bump = ctx.bumps.buyer_bet;
+            buyer_bet.version = ACCOUNT_VERSION;
+            buyer_bet.open_offers = 0;
+            buyer_bet.reserved = [0; 30];
+        }
+
+        let index = offer.outcome_index as usize;
+        buyer_bet.bets[index] = buyer_bet.bets[index].checked_add(offer.amount).ok_or(ErrorCode::Overflow)?;
+        buyer_bet.total_bet = buyer_bet.total_bet.checked_add(offer.amount).ok_or(ErrorCode::Overflow)?;
+        ctx.accounts.seller_bet.open_offers -= 1;
+
+        emit!(OfferFilled {
+            offer: offer.key(),
+            market: offer.market,
+            seller: offer.seller,
+            buyer,
+            amount: offer.amount,
+            price: offer.price,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw an unfilled offer - the escrowed stake returns to the seller
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+        let offer = &ctx.accounts.offer;
+        let seller_bet = &mut ctx.accounts.seller_bet;
+
+        // Not gated on a pause - the stake goes back into the seller's position, nothing leaves a vault
+        require!(!seller_bet.claimed, ErrorCode::AlreadyClaimed);
+        let index = offer.outcome_index as usize;
+        seller_bet.bets[index] = seller_bet.bets[index].checked_add(offer.amount).ok_or(ErrorCode::Overflow)?;
+        seller_bet.total_bet = seller_bet.total_bet.checked_add(offer.amount).ok_or(ErrorCode::Overflow)?;
+        seller_bet.open_offers -= 1;
+
+        emit!(OfferCancelled {
+            offer: offer.key(),
+            seller: offer.seller,
+        });
+
+        Ok(())
+    }
+
+    /// Place a bet held by a one-of-one receipt token instead of a UserBet
+    pub fn place_bet_with_receipt(
+        ctx: Context<PlaceBetWithReceipt>,
+        outcome_index: u8,
+        amount: u64,
+        uri: String,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let now = Clock::get()?.unix_timestamp;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_BETS)?;
+        require!(uri.len() <= MAX_URI_LEN, ErrorCode::InvalidMetadataUri);
+        check_bet(market, outcome_index, amount, now)?;
+
+        add_to_pools(market, outcome_index, amount)?;
+        let market_key = market.key();
+        let mut name = market.outcomes[outcome_index as usize].clone();
+        while name.len() > MAX_RECEIPT_NAME_LEN {
+            name.pop();
+        }
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.market_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                }
+            ),
+            amount
+        )?;
+
+        // Mint the single receipt token, then hand the mint to a master edition with no prints
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    to: ctx.accounts.receipt_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                }
+            ),
+            1
+        )?;
+        create_receipt_nft(ctx.accounts, name, uri, ctx.bumps.receipt)?;
+
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.market = market_key;
+        receipt.mint = ctx.accounts.receipt_mint.key();
+        receipt.outcome_index = outcome_index;
+        receipt.amount = amount;
+        receipt.placed_at = now;
+        receipt.claimed = false;
+        receipt.bump = ctx.bumps.receipt;
+        receipt.version = ACCOUNT_VERSION;
+        receipt.reserved = [0; 32];
+
+        emit!(ReceiptMinted {
+            market: receipt.market,
+            mint: receipt.mint,
+            user: ctx.accounts.user.key(),
+            outcome_index,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Pay a receipt's winnings, or its refund if the market closed, to whoever holds it
+    pub fn claim_receipt(ctx: Context<ClaimReceipt>) -> Result<()> {
+        let market = &ctx.accounts.market;
+        let receipt = &mut ctx.accounts.receipt;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
+        require!(market.resolved, ErrorCode::NotResolved);
+        require!(!receipt.claimed, ErrorCode::AlreadyClaimed);
+        require!(!market.disputed, ErrorCode::MarketDisputed);
+
+        let payout = match market.winner {
+            Some(winner) => {
+                require!(
+                    Clock::get()?.unix_timestamp >= dispute_deadline(market),
+                    ErrorCode::DisputeWindowOpen
+                );
+                require!(receipt.outcome_index == winner, ErrorCode::NoWinningBet);
+
+                let (platform_fee, resolver_fee) = settlement_fees(market)?;
+                let prize_pool = market.total_pool - platform_fee - resolver_fee;
+                let winnings = winning_payout(receipt.amount, market.outcome_pools[winner as usize], prize_pool)?;
+                require!(winnings >= receipt.amount, ErrorCode::InvalidPayout);
+                winnings
+            }
+            None => refund_payout(receipt.amount, market)?,
+        };
+
+        let market_key = market.key();
+        let seeds = &[
+            b"vault",
+            market_key.as_ref(),
+            &[ctx.bumps.market_vault],
+        ];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.market_vault.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.market_vault.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            payout
+        )?;
+
+        // Burn the receipt so a paid-out position can't be resold, and hand the holder its rent
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    from: ctx.accounts.receipt_token_account.to_account_info(),
+                    authority: ctx.accounts.holder.to_account_info(),
+                }
+            ),
+            1
+        )?;
+        token::close_account(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: ctx.accounts.receipt_token_account.to_account_info(),
+                    destination: ctx.accounts.holder.to_account_info(),
+                    authority: ctx.accounts.holder.to_account_info(),
+                }
+            )
+        )?;
+
+        receipt.claimed = true;
+
+        emit!(ReceiptClaimed {
+            market: market_key,
+            mint: receipt.mint,
+            holder: ctx.accounts.holder.key(),
+            amount: payout,
+        });
+
+        Ok(())
+    }
+
+    /// Let a session key bet from the user's token account, within an expiry and spending cap
+    pub fn create_delegate(
+        ctx: Context<CreateDelegate>,
+        session_key: Pubkey,
+        expires_at: i64,
+        spending_cap: u64,
+        markets: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(expires_at > Clock::get()?.unix_timestamp, ErrorCode::InvalidDuration);
+        require!(spending_cap > 0, ErrorCode::InvalidAmount);
+        require!(markets.len() <= MAX_DELEGATE_MARKETS, ErrorCode::TooManyMarkets);
+
+        let delegate = &mut ctx.accounts.delegate;
+        delegate.user = ctx.accounts.user.key();
+        delegate.session_key = session_key;
+        delegate.expires_at = expires_at;
+        delegate.spending_cap = spending_cap;
+        delegate.spent = 0;
+        delegate.markets = markets;
+        delegate.bump = ctx.bumps.delegate;
+        delegate.version = ACCOUNT_VERSION;
+        delegate.reserved = [0; 32];
+
+        // Every session key shares the user's one spend authority approval - add this cap to it
+        let allowance = spend_allowance(&ctx.accounts.user_token_account, &ctx.accounts.spend_authority).unwrap_or(0);
+        token::approve(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Approve {
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    delegate: ctx.accounts.spend_authority.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                }
+            ),
+            allowance.checked_add(spending_cap).ok_or(ErrorCode::Overflow)?
+        )?;
+
+        emit!(DelegateCreated {
+            user: delegate.user,
+            session_key,
+            expires_at,
+            spending_cap,
+        });
+
+        Ok(())
+    }
+
+    /// Remove a session key and take its unspent cap back out of the shared approval
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        let delegate = &ctx.accounts.delegate;
+        let unspent = delegate.spending_cap.saturating_sub(delegate.spent);
+
+        // Leave an approval the user has since given to someone else alone
+        if let Some(allowance) = spend_allowance(&ctx.accounts.user_token_account, &ctx.accounts.spend_authority) {
+            let remaining = allowance.saturating_sub(unspent);
+            if remaining > 0 {
+                token::approve(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        token::Approve {
+                            to: ctx.accounts.user_token_account.to_account_info(),
+                            delegate: ctx.accounts.spend_authority.to_account_info(),
+                            authority: ctx.accounts.user.to_account_info(),
+                        }
+                    ),
+                    remaining
+                )?;
+            } else {
+                token::revoke(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        token::Revoke {
+                            source: ctx.accounts.user_token_account.to_account_info(),
+                            authority: ctx.accounts.user.to_account_info(),
+                        }
+                    )
+                )?;
+            }
+        }
+
+        emit!(DelegateRevoked {
+            user: ctx.accounts.delegate.user,
+            session_key: ctx.accounts.delegate.session_key,
+        });
+
+        Ok(())
+    }
+
+    /// Place a bet for the user, signed by their session key
+    pub fn place_bet_delegated(
+        ctx: Context<PlaceBetDelegated>,
+        outcome_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let delegate = &mut ctx.accounts.delegate;
+        let now = Clock::get()?.unix_timestamp;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_BETS)?;
+        require!(now < delegate.expires_at, ErrorCode::DelegateExpired);
+        require!(
+            delegate.markets.is_empty() || delegate.markets.contains(&market.key()),
+            ErrorCode::MarketNotAllowed
+        );
+        check_bet(market, outcome_index, amount, now)?;
+
+        delegate.spent = delegate.spent.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        require!(delegate.spent <= delegate.spending_cap, ErrorCode::SpendingCapExceeded);
+
+        add_to_pools(market, outcome_index, amount)?;
+
+        let user = delegate.user;
+        let seeds = &[
+            b"spender",
+            user.as_ref(),
+            &[ctx.bumps.spend_authority],
+        ];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.market_vault.to_account_info(),
+                    authority: ctx.accounts.spend_authority.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            amount
+        )?;
+
+        credit_user_bet(
+            &mut ctx.accounts.user_bet,
+            market,
+            user,
+            ctx.bumps.user_bet,
+            outcome_index,
+            amount,
+        )?;
+
+        emit!(BetPlaced {
+            user,
+            market: market.key(),
+            market_id: market.id,
+            outcome_index,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Set how much relayers may spend for the user in total, replacing any earlier budget
+    pub fn set_relay_budget(ctx: Context<SetRelayBudget>, spending_cap: u64) -> Result<()> {
+        let relay_nonce = &mut ctx.accounts.relay_nonce;
+        let unspent = relay_nonce.spending_cap.saturating_sub(relay_nonce.spent);
+
+        // Swap the old budget's unspent part for the new one, leaving session key caps in the approval
+        let current = spend_allowance(&ctx.accounts.user_token_account, &ctx.accounts.spend_authority);
+        let allowance = current.unwrap_or(0).saturating_sub(unspent)
+            .checked_add(spending_cap).ok_or(ErrorCode::Overflow)?;
+        if allowance > 0 {
+            token::approve(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Approve {
+                        to: ctx.accounts.user_token_account.to_account_info(),
+                        delegate: ctx.accounts.spend_authority.to_account_info(),
+                        authority: ctx.accounts.user.to_account_info(),
+                    }
+                ),
+                allowance
+            )?;
+        } else if current.is_some() {
+            token::revoke(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Revoke {
+                        source: ctx.accounts.user_token_account.to_account_info(),
+                        authority: ctx.accounts.user.to_account_info(),
+                    }
+                )
+            )?;
+        }
+
+        relay_nonce.spending_cap = spending_cap;
+        relay_nonce.spent = 0;
+        relay_nonce.bump = ctx.bumps.relay_nonce;
+        relay_nonce.version = ACCOUNT_VERSION;
+        relay_nonce.reserved = [0; 8];
+
+        emit!(RelayBudgetSet {
+            user: ctx.accounts.user.key(),
+            spending_cap,
+        });
+
+        Ok(())
+    }
+
+    /// Place a bet from a user-signed intent - the relayer pays fees and rent and keeps `relayer_fee`
+    pub fn place_bet_relayed(
+        ctx: Context<PlaceBetRelayed>,
+        outcome_index: u8,
+        amount: u64,
+        relayer_fee: u64,
+        nonce: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let user = ctx.accounts.user.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_BETS)?;
+        require!(now < expires_at, ErrorCode::IntentExpired);
+        require!(nonce >= ctx.accounts.relay_nonce.next_nonce, ErrorCode::NonceUsed);
+
+        let intent = BetIntent {
+            program: crate::ID,
+            relayer: ctx.accounts.relayer.key(),
+            market: market.key(),
+            outcome_index,
+            amount,
+            relayer_fee,
+            nonce,
+            expires_at,
+        };
+        verify_intent_signature(&ctx.accounts.instructions, &user, &intent.try_to_vec()?)?;
+
+        // The relayer is reimbursed out of the signed amount
+        let stake = amount.checked_sub(relayer_fee).ok_or(ErrorCode::InvalidAmount)?;
+        check_bet(market, outcome_index, stake, now)?;
+
+        // Relayed bets draw on their own budget, never on a session key's cap
+        let relay_nonce = &mut ctx.accounts.relay_nonce;
+        relay_nonce.spent = relay_nonce.spent.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        require!(relay_nonce.spent <= relay_nonce.spending_cap, ErrorCode::SpendingCapExceeded);
+        relay_nonce.next_nonce = nonce.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+        add_to_pools(market, outcome_index, stake)?;
+
+        let seeds = &[
+            b"spender",
+            user.as_ref(),
+            &[ctx.bumps.spend_authority],
+        ];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.market_vault.to_account_info(),
+                    authority: ctx.accounts.spend_authority.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            stake
+        )?;
+
+        if relayer_fee > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.user_token_account.to_account_info(),
+                        to: ctx.accounts.relayer_token_account.to_account_info(),
+                        authority: ctx.accounts.spend_authority.to_account_info(),
+                    },
+                    &[&seeds[..]]
+                ),
+                relayer_fee
+            )?;
+        }
+
+        credit_user_bet(
+            &mut ctx.accounts.user_bet,
+            market,
+            user,
+            ctx.bumps.user_bet,
+            outcome_index,
+            stake,
+        )?;
+
+        emit!(BetPlaced {
+            user,
+            market: market.key(),
+            market_id: market.id,
+            outcome_index,
+            amount: stake,
+        });
+
+        Ok(())
+    }
+
+    /// Set up the juror registry - authority only
+    pub fn initialize_juror_registry(
+        ctx: Context<InitializeJurorRegistry>,
+        min_stake: u64,
+        slash_bps: u16,
+        commit_period: i64,
+        reveal_period: i64,
+        dispute_bond: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(min_stake > 0, ErrorCode::StakeTooLow);
+        require!(slash_bps <= MAX_SLASH_BPS, ErrorCode::InvalidSlashBps);
+        require!(commit_period > 0 && reveal_period > 0, ErrorCode::InvalidPeriod);
+
+        let registry = &mut ctx.accounts.juror_registry;
+        registry.mint = ctx.accounts.mint.key();
+        registry.min_stake = min_stake;
+        registry.slash_bps = slash_bps;
+        registry.commit_period = commit_period;
+        registry.reveal_period = reveal_period;
+        registry.dispute_bond = dispute_bond;
+        registry.jurors = Vec::new();
+        registry.bump = ctx.bumps.juror_registry;
+        registry.version = ACCOUNT_VERSION;
+        registry.reserved = [0; 32];
+
+        emit!(JurorRegistryInitialized {
+            mint: registry.mint,
+            min_stake,
+            slash_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Register as a juror by staking collateral
+    pub fn register_juror(ctx: Context<RegisterJuror>, stake: u64) -> Result<()> {
+        let registry = &mut ctx.accounts.juror_registry;
+
+        require!(stake >= registry.min_stake, ErrorCode::StakeTooLow);
+        require!(registry.jurors.len() < MAX_JURORS, ErrorCode::RegistryFull);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.juror_token_account.to_account_info(),
+                    to: ctx.accounts.juror_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                }
+            ),
+            stake
+        )?;
+
+        let juror = &mut ctx.accounts.juror;
+        juror.juror = ctx.accounts.authority.key();
+        juror.stake = stake;
+        juror.bump = ctx.bumps.juror;
+        juror.version = ACCOUNT_VERSION;
+        juror.reserved = [0; 32];
+
+        registry.jurors.push(JurorEntry {
+            juror: juror.juror,
+            active_disputes: 0,
+        });
+
+        emit!(JurorRegistered {
+            juror: juror.juror,
+            stake,
+        });
+
+        Ok(())
+    }
+
+    /// Leave the registry and recover remaining stake
+    pub fn withdraw_juror(ctx: Context<WithdrawJuror>) -> Result<()> {
+        let juror = &ctx.accounts.juror;
+        let registry = &mut ctx.accounts.juror_registry;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
+        // Drawn jurors stay until every dispute they sit on is settled
+        require!(
+            registry.jurors.iter().all(|entry| entry.juror != juror.juror || entry.active_disputes == 0),
+            ErrorCode::JurorBusy
+        );
+
+        let seeds = &[
+            b"juror_vault".as_ref(),
+            &[ctx.bumps.juror_vault],
+        ];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.juror_vault.to_account_info(),
+                    to: ctx.accounts.juror_token_account.to_account_info(),
+                    authority: ctx.accounts.juror_vault.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            juror.stake
+        )?;
+
+        registry.jurors.retain(|entry| entry.juror != juror.juror);
+
+        emit!(JurorWithdrawn {
+            juror: juror.juror,
+            amount: juror.stake,
+        });
+
+        Ok(())
+    }
+
+    /// Challenge a resolution - posts the dispute bond and draws a juror panel from recent slot hashes
+    pub fn open_dispute(ctx: Context<OpenDispute>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let registry = &mut ctx.accounts.juror_registry;
+        let now = Clock::get()?.unix_timestamp;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_RESOLUTION)?;
+        require!(market.resolved, ErrorCode::NotResolved);
+        // Overturning one grouped market would break the group's single winner
+        require!(!market.in_group, ErrorCode::MarketInGroup);
+        let original_winner = market.winner.ok_or(ErrorCode::MarketClosed)?;
+        require!(!market.disputed, ErrorCode::MarketDisputed);
+        require!(now < dispute_deadline(market), ErrorCode::DisputeWindowClosed);
+        require!(ctx.accounts.user_bet.total_bet > 0, ErrorCode::NoPosition);
+        require!(registry.jurors.len() >= PANEL_SIZE, ErrorCode::NotEnoughJurors);
+
+        // Held in the juror vault until settle_dispute_bond
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.challenger_token_account.to_account_info(),
+                    to: ctx.accounts.juror_vault.to_account_info(),
+                    authority: ctx.accounts.challenger.to_account_info(),
+                }
+            ),
+            registry.dispute_bond
+        )?;
+
+        let entropy = recent_slot_hash(&ctx.accounts.slot_hashes)?;
+        let pool: Vec<Pubkey> = registry.jurors.iter().map(|entry| entry.juror).collect();
+        let panel = draw_panel(&pool, &entropy, &market.key());
+
+        // Drawn jurors are busy from now on, whether or not they ever vote
+        for entry in registry.jurors.iter_mut().filter(|entry| panel.contains(&entry.juror)) {
+            entry.active_disputes = entry.active_disputes.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        }
+
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.market = market.key();
+        dispute.challenger = ctx.accounts.challenger.key();
+        dispute.original_winner = original_winner;
+        dispute.panel = panel.clone();
+        dispute.commitments = vec![[0u8; 32]; PANEL_SIZE];
+        dispute.votes = vec![None; PANEL_SIZE];
+        dispute.settled = vec![false; PANEL_SIZE];
+        dispute.commit_deadline = now + registry.commit_period;
+        dispute.reveal_deadline = dispute.commit_deadline + registry.reveal_period;
+        dispute.finalized = false;
+        dispute.final_outcome = None;
+        dispute.bond = registry.dispute_bond;
+        dispute.bond_settled = false;
+        dispute.bump = ctx.bumps.dispute;
+        dispute.version = ACCOUNT_VERSION;
+        dispute.reserved = [0; 32];
+
+        market.disputed = true;
+
+        emit!(DisputeOpened {
+            market_id: market.id,
+            challenger: dispute.challenger,
+            panel,
+        });
+
+        Ok(())
+    }
+
+    /// Commit a hidden vote - hash(outcome, salt, juror)
+    pub fn commit_vote(ctx: Context<CommitVote>, commitment: [u8; 32]) -> Result<()> {
+        let dispute = &mut ctx.accounts.dispute;
+        let juror_key = ctx.accounts.authority.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_RESOLUTION)?;
+        require!(now < dispute.commit_deadline, ErrorCode::CommitPhaseOver);
+        let seat = dispute.panel.iter().position(|key| *key == juror_key)
+            .ok_or(ErrorCode::NotOnPanel)?;
+        require!(dispute.commitments[seat] == [0u8; 32], ErrorCode::AlreadyCommitted);
+        require!(commitment != [0u8; 32], ErrorCode::InvalidCommitment);
+
+        dispute.commitments[seat] = commitment;
+
+        emit!(VoteCommitted {
+            market: dispute.market,
+            juror: juror_key,
+        });
+
+        Ok(())
+    }
+
+    /// Reveal a committed vote
+    pub fn reveal_vote(ctx: Context<RevealVote>, outcome: u8, salt: [u8; 32]) -> Result<()> {
+        let dispute = &mut ctx.accounts.dispute;
+        let juror_key = ctx.accounts.juror.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_RESOLUTION)?;
+        require!(now >= dispute.commit_deadline, ErrorCode::CommitPhaseActive);
+        require!(now < dispute.reveal_deadline, ErrorCode::RevealPhaseOver);
+        require!(outcome < ctx.accounts.market.outcomes.len() as u8, ErrorCode::InvalidOutcome);
+
+        let seat = dispute.panel.iter().position(|key| *key == juror_key)
+            .ok_or(ErrorCode::NotOnPanel)?;
+        require!(dispute.commitments[seat] != [0u8; 32], ErrorCode::NotCommitted);
+        require!(dispute.votes[seat].is_none(), ErrorCode::AlreadyRevealed);
+        require!(
+            vote_commitment(outcome, &salt, &juror_key) == dispute.commitments[seat],
+            ErrorCode::InvalidReveal
+        );
+
+        dispute.votes[seat] = Some(outcome);
+
+        emit!(VoteRevealed {
+            market: dispute.market,
+            juror: juror_key,
+            outcome,
+        });
+
+        Ok(())
+    }
+
+    /// Tally revealed votes - permissionless once the reveal phase ends
+    pub fn finalize_dispute(ctx: Context<FinalizeDispute>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let dispute = &mut ctx.accounts.dispute;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_RESOLUTION)?;
+        require!(!dispute.finalized, ErrorCode::DisputeFinalized);
+        require!(
+            Clock::get()?.unix_timestamp >= dispute.reveal_deadline,
+            ErrorCode::RevealPhaseActive
+        );
+
+        // Plurality of revealed votes; no votes or a tie keeps the original call
+        let mut tally = vec![0u8; market.outcomes.len()];
+        for vote in dispute.votes.iter().flatten() {
+            tally[*vote as usize] += 1;
+        }
+        let top = tally.iter().copied().max().unwrap_or(0);
+        let leaders: Vec<usize> = (0..tally.len()).filter(|i| tally[*i] == top).collect();
+        let outcome = if top > 0 && leaders.len() == 1 {
+            leaders[0] as u8
+        } else {
+            dispute.original_winner
+        };
+
+        let overturned = outcome != dispute.original_winner;
+        if overturned {
+            // Nobody backed the corrected outcome - fall back to refunds
+            market.winner = if market.outcome_pools[outcome as usize] > 0 {
+                Some(outcome)
+            } else {
+                None
+            };
+
+            if market.resolved_by == market.oracle && market.resolved_by != Pubkey::default() {
+                // The profile PDA is always passed, so a registered oracle can't dodge the slash by omission
+                let profile_info = ctx.accounts.oracle_profile.to_account_info();
+                let registered = profile_info.owner == &crate::ID && !profile_info.data_is_empty();
+                require!(
+                    market.min_oracle_stake == 0 || registered,
+                    ErrorCode::OracleNotRegistered
+                );
+
+                if registered {
+                    let mut profile = OracleProfile::try_deserialize(&mut &profile_info.try_borrow_data()?[..])?;
+                    profile.disputes_lost = profile.disputes_lost.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+                    // Slash into the pool - winners share it, or refunds are paid out pro-rata with it
+                    let slash = (profile.stake as u128 * ORACLE_SLASH_BPS as u128 / 10000) as u64;
+                    if slash > 0 {
+                        let (Some(oracle_vault), Some(market_vault), Some(token_program)) = (
+                            ctx.accounts.oracle_vault.as_ref(),
+                            ctx.accounts.market_vault.as_ref(),
+                            ctx.accounts.token_program.as_ref(),
+                        ) else {
+                            return err!(ErrorCode::MissingSlashAccounts);
+                        };
+
+                        // A stake in another mint can't go into this pool - the loss is still recorded
+                        if oracle_vault.mint == market_vault.mint {
+                            let oracle_key = profile.oracle;
+                            let seeds = &[
+                                b"oracle_vault".as_ref(),
+                                oracle_key.as_ref(),
+                                &[ctx.bumps.oracle_vault],
+                            ];
+
+                            token::transfer(
+                                CpiContext::new_with_signer(
+                                    token_program.to_account_info(),
+                                    Transfer {
+                                        from: oracle_vault.to_account_info(),
+                                        to: market_vault.to_account_info(),
+                                        authority: oracle_vault.to_account_info(),
+                                    },
+                                    &[&seeds[..]]
+                                ),
+                                slash
+                            )?;
+
+                            profile.stake -= slash;
+                            market.total_pool = market.total_pool.checked_add(slash).ok_or(ErrorCode::Overflow)?;
+
+                            emit!(OracleSlashed {
+                                oracle: oracle_key,
+                                market_id: market.id,
+                                amount: slash,
+                            });
+                        }
+                    }
+
+                    profile.try_serialize(&mut &mut profile_info.try_borrow_mut_data()?[..])?;
+                }
+            }
+
+            // A wrong call earns nothing - the bounty goes to winners, or back to the creator on refunds
+            market.resolved_by = Pubkey::default();
+            if market.winner.is_some() {
+                market.total_pool = market.total_pool.checked_add(market.resolver_bounty).ok_or(ErrorCode::Overflow)?;
+                market.resolver_bounty = 0;
+            }
+        }
+
+        dispute.finalized = true;
+        dispute.final_outcome = Some(outcome);
+        market.disputed = false;
+
+        emit!(DisputeFinalized {
+            market_id: market.id,
+            original_winner: dispute.original_winner,
+            outcome,
+            overturned,
+        });
+
+        Ok(())
+    }
+
+    /// Release a panel juror - slashes anyone outside the majority or who never revealed
+    pub fn settle_juror(ctx: Context<SettleJuror>) -> Result<()> {
+        let dispute = &mut ctx.accounts.dispute;
+        let registry = &mut ctx.accounts.juror_registry;
+        let juror = &mut ctx.accounts.juror;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
+        require!(dispute.finalized, ErrorCode::DisputeNotFinalized);
+        let seat = dispute.panel.iter().position(|key| *key == juror.juror)
+            .ok_or(ErrorCode::NotOnPanel)?;
+        require!(!dispute.settled[seat], ErrorCode::AlreadySettled);
+
+        dispute.settled[seat] = true;
+
+        // No vote counts as a wrong vote, whether the juror never committed or never revealed
+        if dispute.votes[seat] != dispute.final_outcome {
+            let slash = (juror.stake as u128 * registry.slash_bps as u128 / 10000) as u64;
+
+            if slash > 0 {
+                let seeds = &[
+                    b"juror_vault".as_ref(),
+                    &[ctx.bumps.juror_vault],
+                ];
+
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.juror_vault.to_account_info(),
+                            to: ctx.accounts.fee_token_account.to_account_info(),
+                            authority: ctx.accounts.juror_vault.to_account_info(),
+                        },
+                        &[&seeds[..]]
+                    ),
+                    slash
+                )?;
+
+                juror.stake -= slash;
+
+                emit!(JurorSlashed {
+                    juror: juror.juror,
+                    market: dispute.market,
+                    amount: slash,
+                });
+            }
+        }
+
+        if let Some(entry) = registry.jurors.iter_mut().find(|entry| entry.juror == juror.juror) {
+            entry.active_disputes = entry.active_disputes.saturating_sub(1);
+            // Drop out of future panels once under the minimum and free of open disputes
+            if entry.active_disputes == 0 && juror.stake < registry.min_stake {
+                registry.jurors.retain(|entry| entry.juror != juror.juror);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the dispute bond if the ruling was overturned, otherwise pay it to the fee recipient
+    pub fn settle_dispute_bond(ctx: Context<SettleDisputeBond>) -> Result<()> {
+        let dispute = &mut ctx.accounts.dispute;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
+        require!(dispute.finalized, ErrorCode::DisputeNotFinalized);
+        require!(!dispute.bond_settled, ErrorCode::AlreadyClaimed);
+
+        let upheld = dispute.final_outcome == Some(dispute.original_winner);
+        let recipient = if upheld {
+            ctx.accounts.global_state.fee_recipient
+        } else {
+            dispute.challenger
+        };
+        require!(ctx.accounts.destination.owner == recipient, ErrorCode::Unauthorized);
+
+        let seeds = &[
+            b"juror_vault".as_ref(),
+            &[ctx.bumps.juror_vault],
+        ];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.juror_vault.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.juror_vault.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            dispute.bond
+        )?;
+
+        dispute.bond_settled = true;
+
+        emit!(DisputeBondSettled {
+            market: dispute.market,
+            recipient,
+            amount: dispute.bond,
+            forfeited: upheld,
+        });
+
+        Ok(())
+    }
+
+    /// Register as an oracle by staking collateral
+    pub fn register_oracle(ctx: Context<RegisterOracle>, stake: u64) -> Result<()> {
+        require!(stake > 0, ErrorCode::StakeTooLow);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.oracle_token_account.to_account_info(),
+                    to: ctx.accounts.oracle_vault.to_account_info(),
+                    authority: ctx.accounts.oracle.to_account_info(),
+                }
+            ),
+            stake
+        )?;
+
+        let profile = &mut ctx.accounts.oracle_profile;
+        profile.oracle = ctx.accounts.oracle.key();
+        profile.mint = ctx.accounts.mint.key();
+        profile.stake = stake;
+        profile.markets_resolved = 0;
+        profile.disputes_lost = 0;
+        profile.avg_latency = 0;
+        profile.unstake_requested_at = 0;
+        profile.bump = ctx.bumps.oracle_profile;
+        profile.version = ACCOUNT_VERSION;
+        profile.reserved = [0; 32];
+
+        emit!(OracleRegistered {
+            oracle: profile.oracle,
+            mint: profile.mint,
+            stake,
+        });
+
+        Ok(())
+    }
+
+    /// Top up oracle stake
+    pub fn add_oracle_stake(ctx: Context<AddOracleStake>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::StakeTooLow);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.oracle_token_account.to_account_info(),
+                    to: ctx.accounts.oracle_vault.to_account_info(),
+                    authority: ctx.accounts.oracle.to_account_info(),
+                }
+            ),
+            amount
+        )?;
+
+        let profile = &mut ctx.accounts.oracle_profile;
+        profile.stake = profile.stake.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+        emit!(OracleStakeChanged {
+            oracle: profile.oracle,
+            stake: profile.stake,
+        });
+
+        Ok(())
+    }
+
+    /// Start the unstake cooldown - staked markets can't be resolved meanwhile
+    pub fn request_oracle_unstake(ctx: Context<RequestOracleUnstake>) -> Result<()> {
+        let profile = &mut ctx.accounts.oracle_profile;
+        profile.unstake_requested_at = Clock::get()?.unix_timestamp;
+
+        emit!(OracleUnstakeRequested {
+            oracle: profile.oracle,
+            available_at: profile.unstake_requested_at + ORACLE_UNSTAKE_COOLDOWN,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw oracle stake after the cooldown
+    pub fn withdraw_oracle_stake(ctx: Context<WithdrawOracleStake>, amount: u64) -> Result<()> {
+        let profile = &mut ctx.accounts.oracle_profile;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
+        require!(profile.unstake_requested_at > 0, ErrorCode::UnstakeNotRequested);
+        require!(
+            Clock::get()?.unix_timestamp >= profile.unstake_requested_at + ORACLE_UNSTAKE_COOLDOWN,
+            ErrorCode::CooldownActive
+        );
+        require!(amount > 0 && amount <= profile.stake, ErrorCode::InsufficientStake);
+
+        let oracle_key = profile.oracle;
+        let seeds = &[
+            b"oracle_vault".as_ref(),
+            oracle_key.as_ref(),
+            &[ctx.bumps.oracle_vault],
+        ];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.oracle_vault.to_account_info(),
+                    to: ctx.accounts.oracle_token_account.to_account_info(),
+                    authority: ctx.accounts.oracle_vault.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            amount
+        )?;
+
+        profile.stake -= amount;
+        profile.unstake_requested_at = 0;
+
+        emit!(OracleStakeChanged {
+            oracle: oracle_key,
+            stake: profile.stake,
+        });
+
+        Ok(())
+    }
+
+    /// Claim resolver fee and bounty - only the key that resolved
+    pub fn claim_resolver_fee(ctx: Context<ClaimResolverFee>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
+        require!(market.resolved && market.winner.is_some(), ErrorCode::NotResolved);
+        require!(market.resolved_by == ctx.accounts.resolver.key(), ErrorCode::Unauthorized);
+        require!(!market.resolver_paid, ErrorCode::AlreadyClaimed);
+        require!(!market.disputed, ErrorCode::MarketDisputed);
+        require!(
+            Clock::get()?.unix_timestamp >= dispute_deadline(market),
+            ErrorCode::DisputeWindowOpen
+        );
+
+        let (_, resolver_fee) = settlement_fees(market)?;
+        let amount = resolver_fee.checked_add(market.resolver_bounty).ok_or(ErrorCode::Overflow)?;
+
+        market.resolver_paid = true;
+
+        if amount > 0 {
+            let market_key = market.key();
+            let seeds = &[
+                b"vault".as_ref(),
+                market_key.as_ref(),
+                &[ctx.bumps.market_vault],
+            ];
+
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.market_vault.to_account_info(),
+                        to: ctx.accounts.resolver_token_account.to_account_info(),
+                        authority: ctx.accounts.market_vault.to_account_info(),
+                    },
+                    &[&seeds[..]]
+                ),
+                amount
+            )?;
+        }
+
+        emit!(ResolverPaid {
+            market_id: market.id,
+            resolver: market.resolved_by,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Return an unearned bounty to the creator of a closed market
+    pub fn reclaim_resolver_bounty(ctx: Context<ReclaimResolverBounty>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
+        require!(market.resolved, ErrorCode::NotResolved);
+        require!(market.winner.is_none(), ErrorCode::MarketNotClosed);
+        require!(market.resolver_bounty > 0, ErrorCode::NoRefundAvailable);
+
+        let amount = market.resolver_bounty;
+        market.resolver_bounty = 0;
+
+        let market_key = market.key();
+        let seeds = &[
+            b"vault".as_ref(),
+            market_key.as_ref(),
+            &[ctx.bumps.market_vault],
+        ];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.market_vault.to_account_info(),
+                    to: ctx.accounts.creator_token_account.to_account_info(),
+                    authority: ctx.accounts.market_vault.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            amount
+        )?;
+
+        emit!(BountyReclaimed {
+            market_id: market.id,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Set the creation bond for new markets - authority only
+    pub fn set_creation_bond(ctx: Context<SetCreationBond>, amount: u64) -> Result<()> {
+        let state = &mut ctx.accounts.global_state;
+
+        require!(ctx.accounts.authority.key() == state.authority, ErrorCode::Unauthorized);
+
+        state.creation_bond = amount;
+
+        emit!(CreationBondUpdated {
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Settle the creation bond - refund on clean resolution, treasury on closure
+    pub fn settle_creation_bond(ctx: Context<SettleCreationBond>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
+        require!(market.resolved, ErrorCode::NotResolved);
+        require!(!market.bond_settled, ErrorCode::AlreadyClaimed);
+
+        // Closed markets settle immediately; resolutions wait out the dispute window
+        if !market.bond_forfeited {
+            require!(!market.disputed, ErrorCode::MarketDisputed);
+            require!(
+                Clock::get()?.unix_timestamp >= dispute_deadline(market),
+                ErrorCode::DisputeWindowOpen
+            );
+        }
+
+        market.bond_settled = true;
+
+        let market_key = market.key();
+        let seeds = &[
+            b"bond".as_ref(),
+            market_key.as_ref(),
+            &[ctx.bumps.bond_vault],
+        ];
+
+        let destination = if market.bond_forfeited {
+            ctx.accounts.treasury_token_account.to_account_info()
+        } else {
+            ctx.accounts.creator_token_account.to_account_info()
+        };
+
+        if market.creation_bond > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.bond_vault.to_account_info(),
+                        to: destination,
+                        authority: ctx.accounts.bond_vault.to_account_info(),
+                    },
+                    &[&seeds[..]]
+                ),
+                market.creation_bond
+            )?;
+        }
+
+        // Bond vault rent goes back to the creator who paid it
+        token::close_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: ctx.accounts.bond_vault.to_account_info(),
+                    destination: ctx.accounts.creator.to_account_info(),
+                    authority: ctx.accounts.bond_vault.to_account_info(),
+                },
+                &[&seeds[..]]
+            )
+        )?;
+
+        emit!(CreationBondSettled {
+            market_id: market.id,
+            amount: market.creation_bond,
+            forfeited: market.bond_forfeited,
+        });
+
+        Ok(())
+    }
+
+    /// Switch between permissionless and allowlisted market creation - authority only
+    pub fn set_creation_mode(ctx: Context<SetCreationMode>, permissioned: bool) -> Result<()> {
+        let state = &mut ctx.accounts.global_state;
+
+        require!(ctx.accounts.authority.key() == state.authority, ErrorCode::Unauthorized);
+
+        state.permissioned_creation = permissioned;
+
+        emit!(CreationModeUpdated {
+            permissioned,
+        });
+
+        Ok(())
+    }
+
+    /// Issue or update a creator permit - authority only
+    pub fn issue_permit(
+        ctx: Context<IssuePermit>,
+        creator: Pubkey,
+        max_open_markets: u32,
+        allowed_mints: Vec<Pubkey>,
+        max_duration: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(allowed_mints.len() <= MAX_PERMIT_MINTS, ErrorCode::TooManyMints);
+        require!(max_duration >= 0, ErrorCode::InvalidPeriod);
+
+        // Re-issuing, even after a revoke, keeps the open market count
+        let permit = &mut ctx.accounts.creator_permit;
+        if permit.creator == Pubkey::default() {
+            permit.creator = creator;
+            permit.open_markets = 0;
+            permit.bump = ctx.bumps.creator_permit;
+            permit.version = ACCOUNT_VERSION;
+            permit.reserved = [0; 32];
+        }
+        permit.revoked = false;
+        permit.max_open_markets = max_open_markets;
+        permit.allowed_mints = allowed_mints;
+        permit.max_duration = max_duration;
+
+        emit!(PermitIssued {
+            creator,
+            max_open_markets,
+            max_duration,
+        });
+
+        Ok(())
+    }
+
+    /// Nominate a new authority - takes effect once accepted
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        let state = &mut ctx.accounts.global_state;
+
+        require!(ctx.accounts.authority.key() == state.authority, ErrorCode::Unauthorized);
+        require!(new_authority != Pubkey::default(), ErrorCode::InvalidAuthority);
+        consume_change(&ctx.accounts.pending_change, &ConfigChange::Authority { new_authority })?;
+
+        state.pending_authority = new_authority;
+
+        emit!(AuthorityProposed {
+            authority: state.authority,
+            pending_authority: new_authority,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a pending authority transfer - signed by the nominee
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let state = &mut ctx.accounts.global_state;
+
+        require!(state.pending_authority != Pubkey::default(), ErrorCode::NoPendingAuthority);
+        require!(ctx.accounts.new_authority.key() == state.pending_authority, ErrorCode::Unauthorized);
+
+        let old_authority = state.authority;
+        state.authority = state.pending_authority;
+        state.pending_authority = Pubkey::default();
+
+        emit!(AuthorityTransferred {
+            old_authority,
+            new_authority: state.authority,
+        });
+
+        Ok(())
+    }
+
+    /// Update platform fee and recipient - authority only
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        fee_bps: Option<u16>,
+        fee_recipient: Option<Pubkey>,
+    ) -> Result<()> {
+        let state = &mut ctx.accounts.global_state;
+
+        require!(ctx.accounts.authority.key() == state.authority, ErrorCode::Unauthorized);
+        consume_change(&ctx.accounts.pending_change, &ConfigChange::Config { fee_bps, fee_recipient })?;
+
+        if let Some(fee_bps) = fee_bps {
+            require!(fee_bps <= state.limits.max_fee_bps, ErrorCode::FeeTooHigh);
+
+            emit!(FeeUpdated {
+                old_fee_bps: state.fee_bps,
+                new_fee_bps: fee_bps,
+            });
+            state.fee_bps = fee_bps;
+        }
+
+        if let Some(fee_recipient) = fee_recipient {
+            require!(fee_recipient != Pubkey::default(), ErrorCode::InvalidFeeRecipient);
+
+            emit!(FeeRecipientUpdated {
+                old_fee_recipient: state.fee_recipient,
+                new_fee_recipient: fee_recipient,
+            });
+            state.fee_recipient = fee_recipient;
+        }
+
+        Ok(())
+    }
+
+    /// Queue a sensitive change - executable after TIMELOCK_DELAY
+    pub fn queue_change(ctx: Context<QueueChange>, change: ConfigChange) -> Result<()> {
+        let state = &mut ctx.accounts.global_state;
+
+        require!(ctx.accounts.authority.key() == state.authority, ErrorCode::Unauthorized);
+
+        // Reject bad values now rather than after the delay
+        match &change {
+            ConfigChange::Config { fee_bps, fee_recipient } => {
+                require!(!matches!(fee_bps, Some(fee) if *fee > state.limits.max_fee_bps), ErrorCode::FeeTooHigh);
+                require!(*fee_recipient != Some(Pubkey::default()), ErrorCode::InvalidFeeRecipient);
+            }
+            ConfigChange::Authority { new_authority } => {
+                require!(*new_authority != Pubkey::default(), ErrorCode::InvalidAuthority);
+            }
+            ConfigChange::Oracle { new_oracle, .. } => {
+                require!(*new_oracle != Pubkey::default(), ErrorCode::InvalidOracle);
+            }
+            ConfigChange::MaxFee { max_fee_bps } => {
+                require!(*max_fee_bps <= ABS_MAX_FEE_BPS, ErrorCode::InvalidLimits);
+                require!(state.fee_bps <= *max_fee_bps, ErrorCode::FeeTooHigh);
+            }
+        }
+
+        let pending = &mut ctx.accounts.pending_change;
+        pending.id = state.change_nonce;
+        pending.change = change.clone();
+        pending.eta = Clock::get()?.unix_timestamp + TIMELOCK_DELAY;
+        pending.bump = ctx.bumps.pending_change;
+        pending.version = ACCOUNT_VERSION;
+        pending.reserved = [0; 32];
+
+        state.change_nonce = state.change_nonce.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+        emit!(ChangeQueued {
+            id: pending.id,
+            change,
+            eta: pending.eta,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a queued change during the delay - authority only
+    pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        emit!(ChangeCancelled {
+            id: ctx.accounts.pending_change.id,
+        });
+
+        Ok(())
+    }
+
+    /// Halt bets, creation, claims and/or resolution platform-wide - authority or pauser
+    pub fn set_global_pause(ctx: Context<SetGlobalPause>, paused_ops: u8) -> Result<()> {
+        require!(
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.authority.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::Pauser,
+            ),
+            ErrorCode::Unauthorized
+        );
+        require!(paused_ops & !PAUSE_ALL == 0, ErrorCode::InvalidPauseFlags);
+
+        ctx.accounts.global_state.paused_ops = paused_ops;
+
+        emit!(GlobalPauseUpdated {
+            paused_ops,
+        });
+
+        Ok(())
+    }
+
+    /// Adjust market and fee limits within the absolute bounds - authority only
+    pub fn update_limits(ctx: Context<UpdateLimits>, limits: PlatformLimits) -> Result<()> {
+        let state = &mut ctx.accounts.global_state;
+
+        require!(ctx.accounts.authority.key() == state.authority, ErrorCode::Unauthorized);
+        require!(limits.max_outcomes >= 2 && limits.max_outcomes <= ABS_MAX_OUTCOMES, ErrorCode::InvalidLimits);
+        require!(limits.max_question_len > 0 && limits.max_question_len <= ABS_MAX_QUESTION_LEN, ErrorCode::InvalidLimits);
+        require!(limits.max_outcome_len > 0 && limits.max_outcome_len <= ABS_MAX_OUTCOME_LEN, ErrorCode::InvalidLimits);
+        // The fee cap bounds what bettors can be charged, so it only moves through update_max_fee
+        require!(limits.max_fee_bps == state.limits.max_fee_bps, ErrorCode::FeeCapTimelocked);
+        require!(
+            limits.min_duration >= ABS_MIN_DURATION
+                && limits.max_duration <= ABS_MAX_DURATION
+                && limits.min_duration <= limits.max_duration,
+            ErrorCode::InvalidLimits
+        );
+
+        state.limits = limits;
+
+        emit!(LimitsUpdated {
+            limits,
+        });
+
+        Ok(())
+    }
+
+    /// Set the dispute window for new markets within the absolute bounds - authority only
+    pub fn update_dispute_window(ctx: Context<UpdateLimits>, dispute_window: i64) -> Result<()> {
+        let state = &mut ctx.accounts.global_state;
+
+        require!(ctx.accounts.authority.key() == state.authority, ErrorCode::Unauthorized);
+        require!(
+            (ABS_MIN_DISPUTE_WINDOW..=ABS_MAX_DISPUTE_WINDOW).contains(&dispute_window),
+            ErrorCode::InvalidLimits
+        );
+
+        state.dispute_window = dispute_window;
+
+        emit!(DisputeWindowUpdated {
+            dispute_window,
+        });
+
+        Ok(())
+    }
+
+    /// Change the platform fee cap - authority only, after the timelock
+    pub fn update_max_fee(ctx: Context<UpdateMaxFee>, max_fee_bps: u16) -> Result<()> {
+        let state = &mut ctx.accounts.global_state;
+
+        require!(ctx.accounts.authority.key() == state.authority, ErrorCode::Unauthorized);
+        consume_change(&ctx.accounts.pending_change, &ConfigChange::MaxFee { max_fee_bps })?;
+        require!(max_fee_bps <= ABS_MAX_FEE_BPS, ErrorCode::InvalidLimits);
+        // Lowering the cap must not strand the current fee above it
+        require!(state.fee_bps <= max_fee_bps, ErrorCode::FeeTooHigh);
+
+        state.limits.max_fee_bps = max_fee_bps;
+
+        emit!(LimitsUpdated {
+            limits: state.limits,
+        });
+
+        Ok(())
+    }
+
+    /// Grant an operational role - authority only
+    pub fn grant_role(ctx: Context<GrantRole>, grantee: Pubkey, role: Role) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        let grant = &mut ctx.accounts.role_grant;
+        grant.grantee = grantee;
+        grant.role = role;
+        grant.bump = ctx.bumps.role_grant;
+        grant.version = ACCOUNT_VERSION;
+        grant.reserved = [0; 32];
+
+        emit!(RoleGranted {
+            grantee,
+            role,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke an operational role - authority only
+    pub fn revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        emit!(RoleRevoked {
+            grantee: ctx.accounts.role_grant.grantee,
+            role: ctx.accounts.role_grant.role,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a creator permit - authority only. The account stays so open markets can still free their slots
+    pub fn revoke_permit(ctx: Context<RevokePermit>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.global_state.authority,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.creator_permit.revoked = true;
+
+        emit!(PermitRevoked {
+            creator: ctx.accounts.creator_permit.creator,
+        });
+
+        Ok(())
+    }
+
+    /// Upgrade a legacy GlobalState in place - anyone can pay for it
+    pub fn migrate_global_state(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrate_account::<GlobalState>(
+            &ctx.accounts.account,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            LegacyGlobalState::upgrade,
+        )
+    }
+
+    /// Upgrade a legacy Market in place, growing it if needed
+    pub fn migrate_market(ctx: Context<MigrateMarket>) -> Result<()> {
+        // fee_bps follows the authority in both GlobalState layouts, so it reads the same before and after migration
+        let fee_bps = {
+            let data = ctx.accounts.global_state.try_borrow_data()?;
+            require!(data.len() >= 42, ErrorCode::InvalidGlobalState);
+            u16::from_le_bytes([data[40], data[41]])
+        };
+
+        migrate_account::<Market>(
+            &ctx.accounts.account,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            |legacy: LegacyMarket| legacy.upgrade(fee_bps),
+        )
+    }
+
+    /// Upgrade a legacy UserBet in place, growing it if needed
+    pub fn migrate_user_bet(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrate_account::<UserBet>(
+            &ctx.accounts.account,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            LegacyUserBet::upgrade,
+        )
+    }
+
+    /// Create a recurring market template - roll_series opens each market
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_series(
+        ctx: Context<CreateSeries>,
+        series_id: u64,
+        question: String,
+        outcomes: Vec<String>,
+        oracle: Pubkey,
+        duration: i64,
+        first_end_time: i64,
+        min_bet: u64,
+        category: MarketCategory,
+        rules_hash: [u8; 32],
+    ) -> Result<()> {
+        let global_state = &ctx.accounts.global_state;
+        let limits = global_state.limits;
+
+        require_not_paused(global_state, PAUSE_CREATION)?;
+        // Series markets skip permits and bonds, so allowlisted mode keeps them to the authority
+        require!(
+            !global_state.permissioned_creation || ctx.accounts.creator.key() == global_state.authority,
+            ErrorCode::CreatorNotPermitted
+        );
+        require!(outcomes.len() >= 2 && outcomes.len() <= limits.max_outcomes as usize, ErrorCode::InvalidOutcomes);
+        require!(!question.trim().is_empty() && question.len() <= limits.max_question_len as usize, ErrorCode::InvalidQuestion);
+        for outcome in &outcomes {
+            require!(!outcome.trim().is_empty() && outcome.len() <= limits.max_outcome_len as usize, ErrorCode::InvalidOutcome);
+        }
+        require!(
+            duration >= limits.min_duration && duration <= limits.max_duration,
+            ErrorCode::InvalidDuration
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(first_end_time > now && first_end_time - now >= limits.min_duration, ErrorCode::InvalidEndTime);
+        require!(first_end_time - now <= limits.max_duration, ErrorCode::EndTimeTooFar);
+        require!(min_bet > 0, ErrorCode::InvalidMinBet);
+
+        let series = &mut ctx.accounts.series;
+        series.creator = ctx.accounts.creator.key();
+        series.series_id = series_id;
+        series.question = question;
+        series.outcomes = outcomes;
+        series.oracle = oracle;
+        series.mint = ctx.accounts.mint.key();
+        series.duration = duration;
+        series.min_bet = min_bet;
+        series.category = category;
+        series.rules_hash = rules_hash;
+        series.next_index = 0;
+        series.current_market = Pubkey::default();
+        // First market can open one period before its end time
+        series.last_end_time = first_end_time - duration;
+        series.bump = ctx.bumps.series;
+        series.version = ACCOUNT_VERSION;
+        series.reserved = [0; 32];
+
+        emit!(SeriesCreated {
+            series: series.key(),
+            series_id,
+            creator: series.creator,
+            duration,
+        });
+
+        Ok(())
+    }
+
+    /// Open the next market in a series once the current one has ended - permissionless
+    pub fn roll_series(ctx: Context<RollSeries>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let series = &mut ctx.accounts.series;
+        let limits = ctx.accounts.global_state.limits;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CREATION)?;
+        require!(now >= series.last_end_time, ErrorCode::SeriesNotDue);
+        // Limits may have moved since the series was created
+        require!(
+            series.duration >= limits.min_duration && series.duration <= limits.max_duration,
+            ErrorCode::InvalidDuration
+        );
+
+        // Stay on the series schedule, skipping any periods nobody rolled
+        let missed = (now - series.last_end_time) / series.duration;
+        let mut end_time = series.last_end_time + (missed + 1) * series.duration;
+        // Rolled late in a period - open the next one instead of a market too short to trade
+        if end_time - now < limits.min_duration {
+            end_time += series.duration;
+        }
+        require!(end_time - now <= limits.max_duration, ErrorCode::EndTimeTooFar);
+        let index = series.next_index;
+
+        ctx.accounts.market.set_inner(Market {
+            id: index,
+            creator: series.creator,
+            question: series.question.clone(),
+            outcomes: series.outcomes.clone(),
+            end_time,
+            oracle: series.oracle,
+            min_bet: series.min_bet,
+            total_pool: 0,
+            outcome_pools: vec![0; series.outcomes.len()],
+            resolved: false,
+            winner: None,
+            paused: false,
+            created_at: now,
+            bump: ctx.bumps.market,
+            resolved_at: 0,
+            disputed: false,
+            min_oracle_stake: 0,
+            resolved_by: Pubkey::default(),
+            resolver_fee_bps: 0,
+            resolver_bounty: 0,
+            resolver_paid: false,
+            creation_bond: 0,
+            bond_forfeited: false,
+            bond_settled: true, // No bond vault to settle
+            holds_permit_slot: false,
+            metadata_uri: String::new(),
+            category: series.category,
+            tags: Vec::new(),
+            rules_hash: series.rules_hash,
+            fee_bps: ctx.accounts.global_state.fee_bps,
+            version: ACCOUNT_VERSION,
+            prev_market: series.current_market,
+            in_group: false,
+            fees_collected: false,
+            dispute_window: ctx.accounts.global_state.dispute_window,
+            reserved: [0; 22],
+        });
+
+        let market_key = ctx.accounts.market.key();
+        series.next_index = index.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        series.current_market = market_key;
+        series.last_end_time = end_time;
+        ctx.accounts.global_state.total_markets += 1;
+
+        emit!(MarketCreated {
+            market: market_key,
+            market_id: index,
+            question: series.question.clone(),
+            outcomes: series.outcomes.clone(),
+            end_time,
+            oracle: series.oracle,
+            metadata_uri: String::new(),
+            category: series.category,
+            tags: Vec::new(),
+            rules_hash: series.rules_hash,
+        });
+
+        emit!(SeriesRolled {
+            series: series.key(),
+            market: market_key,
+            index,
+            end_time,
+        });
+
+        Ok(())
+    }
+
+    /// Create an event grouping related markets under one end time and oracle
+    pub fn create_event(
+        ctx: Context<CreateEvent>,
+        event_id: u64,
+        title: String,
+        metadata_uri: String,
+        end_time: i64,
+        oracle: Pubkey,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let limits = ctx.accounts.global_state.limits;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CREATION)?;
+        require!(!title.trim().is_empty() && title.len() <= limits.max_question_len as usize, ErrorCode::InvalidQuestion);
+        require!(metadata_uri.len() <= MAX_URI_LEN, ErrorCode::InvalidMetadataUri);
+        require!(end_time > now && end_time - now >= limits.min_duration, ErrorCode::InvalidEndTime);
+        require!(end_time - now <= limits.max_duration, ErrorCode::EndTimeTooFar);
+
+        let event = &mut ctx.accounts.event;
+        event.creator = ctx.accounts.creator.key();
+        event.event_id = event_id;
+        event.title = title.clone();
+        event.metadata_uri = metadata_uri;
+        event.end_time = end_time;
+        event.oracle = oracle;
+        event.markets = Vec::new();
+        event.paused = false;
+        event.closed = false;
+        event.bump = ctx.bumps.event;
+        event.version = ACCOUNT_VERSION;
+        event.reserved = [0; 32];
+
+        emit!(EventCreated {
+            event: event.key(),
+            event_id,
+            title,
+            end_time,
+            oracle,
+        });
+
+        Ok(())
+    }
+
+    /// Add one of the creator's markets to their event - it must share the end time and oracle
+    pub fn add_event_market(ctx: Context<AddEventMarket>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        let market = &mut ctx.accounts.market;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CREATION)?;
+        require!(!event.closed, ErrorCode::EventClosed);
+        require!(event.markets.len() < MAX_EVENT_MARKETS, ErrorCode::EventFull);
+        require!(!market.resolved, ErrorCode::MarketResolved);
+        require!(!market.in_group, ErrorCode::MarketInGroup);
+        require!(
+            market.end_time == event.end_time && market.oracle == event.oracle,
+            ErrorCode::EventMismatch
+        );
+
+        event.markets.push(market.key());
+        market.paused = market.paused || event.paused;
+
+        let membership = &mut ctx.accounts.membership;
+        membership.event = event.key();
+        membership.market = market.key();
+        membership.bump = ctx.bumps.membership;
+        membership.version = ACCOUNT_VERSION;
+        membership.reserved = [0; 32];
+
+        emit!(EventMarketAdded {
+            event: event.key(),
+            market: market.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Pause or resume betting on every market in an event - authority or pauser
+    pub fn set_event_paused<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateEvent<'info>>,
+        paused: bool,
+    ) -> Result<()> {
+        require!(
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.authority.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::Pauser,
+            ),
+            ErrorCode::Unauthorized
+        );
+        require!(!ctx.accounts.event.closed, ErrorCode::EventClosed);
+
+        for_each_member(&ctx.accounts.event, ctx.remaining_accounts, |market| {
+            market.paused = paused;
+            Ok(())
+        })?;
+        ctx.accounts.event.paused = paused;
+
+        emit!(EventPauseUpdated {
+            event: ctx.accounts.event.key(),
+            paused,
+        });
+
+        Ok(())
+    }
+
+    /// Close every open market in an event for refunds - authority or moderator
+    pub fn close_event<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateEvent<'info>>,
+        forfeit_bonds: bool,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.authority.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::MarketModerator,
+            ),
+            ErrorCode::Unauthorized
+        );
+        require!(!ctx.accounts.event.closed, ErrorCode::EventClosed);
+
+        // Members share the event's creator, so one permit frees every slot
+        for_each_member(&ctx.accounts.event, ctx.remaining_accounts, |market| {
+            market.resolved = true;
+            market.winner = None;
+            market.resolved_at = now;
+            // Only invalid questions lose their bond - a cancelled fixture doesn't
+            market.bond_forfeited = forfeit_bonds;
+            release_permit_slot(market, ctx.accounts.creator_permit.as_mut())?;
+
+            emit!(MarketClosed {
+                market: market.key(),
+                market_id: market.id,
+            });
+            Ok(())
+        })?;
+        ctx.accounts.event.closed = true;
+
+        emit!(EventClosed {
+            event: ctx.accounts.event.key(),
+            bonds_forfeited: forfeit_bonds,
+        });
+
+        Ok(())
+    }
+
+    /// Move the end time of an event and all its open markets - authority or moderator
+    pub fn retime_event<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateEvent<'info>>,
+        new_end_time: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.authority.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::MarketModerator,
+            ),
+            ErrorCode::Unauthorized
+        );
+        let limits = ctx.accounts.global_state.limits;
+        require!(!ctx.accounts.event.closed, ErrorCode::EventClosed);
+        // Betting has closed on the members - moving the end now would reopen them
+        require!(now < ctx.accounts.event.end_time, ErrorCode::EventEnded);
+        require!(new_end_time > now && new_end_time - now >= limits.min_duration, ErrorCode::InvalidEndTime);
+        require!(new_end_time - now <= limits.max_duration, ErrorCode::EndTimeTooFar);
+
+        for_each_member(&ctx.accounts.event, ctx.remaining_accounts, |market| {
+            market.end_time = new_end_time;
+            Ok(())
+        })?;
+
+        let event = &mut ctx.accounts.event;
+        let old_end_time = event.end_time;
+        event.end_time = new_end_time;
+
+        emit!(EventRetimed {
+            event: event.key(),
+            old_end_time,
+            new_end_time,
+        });
+
+        Ok(())
+    }
+
+    /// Create a group of binary markets where exactly one resolves YES
+    pub fn create_exclusive_group(ctx: Context<CreateExclusiveGroup>, group_id: u64, oracle: Pubkey) -> Result<()> {
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CREATION)?;
+        require!(oracle != Pubkey::default(), ErrorCode::InvalidOracle);
+
+        let group = &mut ctx.accounts.group;
+        group.creator = ctx.accounts.creator.key();
+        group.group_id = group_id;
+        group.oracle = oracle;
+        group.mint = ctx.accounts.mint.key();
+        group.markets = Vec::new();
+        group.resolved = false;
+        group.bump = ctx.bumps.group;
+        group.version = ACCOUNT_VERSION;
+        group.reserved = [0; 32];
+
+        emit!(ExclusiveGroupCreated {
+            group: group.key(),
+            group_id,
+            oracle,
+        });
+
+        Ok(())
+    }
+
+    /// Add one of the creator's binary markets - YES is outcome 0, NO is outcome 1
+    pub fn add_group_market(ctx: Context<AddGroupMarket>) -> Result<()> {
+        let group = &mut ctx.accounts.group;
+        let market = &mut ctx.accounts.market;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CREATION)?;
+        require!(!group.resolved, ErrorCode::GroupResolved);
+        require!(group.markets.len() < MAX_GROUP_MARKETS, ErrorCode::GroupFull);
+        require!(market.outcomes.len() == 2, ErrorCode::InvalidOutcomes);
+        require!(!market.resolved, ErrorCode::MarketResolved);
+        require!(!market.in_group, ErrorCode::MarketInGroup);
+        require!(market.oracle == group.oracle, ErrorCode::InvalidOracle);
+        // Conversions move stakes between member vaults, so they all hold the same mint
+        require!(ctx.accounts.market_vault.mint == group.mint, ErrorCode::InvalidMint);
+        // Event cascades could close one member on its own
+        require!(ctx.accounts.event_membership.data_is_empty(), ErrorCode::MarketInEvent);
+
+        group.markets.push(market.key());
+        market.in_group = true;
+
+        emit!(GroupMarketAdded {
+            group: group.key(),
+            market: market.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Resolve every group market at once - YES for the winner, NO for the rest
+    pub fn resolve_group<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveGroup<'info>>,
+        winning_index: u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let group = &mut ctx.accounts.group;
+        let resolver = ctx.accounts.resolver.key();
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_RESOLUTION)?;
+        require!(
+            resolver == group.oracle ||
+            has_role(&ctx.accounts.global_state, &resolver, ctx.accounts.role_grant.as_ref(), Role::Resolver),
+            ErrorCode::Unauthorized
+        );
+        require!(!group.resolved, ErrorCode::GroupResolved);
+        require!((winning_index as usize) < group.markets.len(), ErrorCode::InvalidOutcome);
+        require!(ctx.remaining_accounts.len() == group.markets.len(), ErrorCode::GroupMarketsMismatch);
+
+        for (index, (info, key)) in ctx.remaining_accounts.iter().zip(&group.markets).enumerate() {
+            require_keys_eq!(info.key(), *key, ErrorCode::GroupMarketsMismatch);
+
+            let mut market = Account::<Market>::try_from(info)?;
+            require!(!market.resolved, ErrorCode::AlreadyResolved);
+            require!(now >= market.end_time, ErrorCode::TooEarly);
+
+            if resolver == market.oracle {
+                record_oracle_resolution(&market, ctx.accounts.oracle_profile.as_mut(), now)?;
+            }
+
+            let outcome = if index == winning_index as usize { YES } else { NO };
+            market.resolved = true;
+            // Nobody on the winning side - refund the market instead
+            market.winner = (market.outcome_pools[outcome] > 0).then_some(outcome as u8);
+            market.resolved_at = now;
+            market.resolved_by = resolver;
+            release_permit_slot(&mut market, ctx.accounts.creator_permit.as_mut())?;
+            market.exit(&crate::ID)?;
+
+            match market.winner {
+                Some(winner) => emit!(MarketResolved {
+                    market: market.key(),
+                    market_id: market.id,
+                    winner,
+                }),
+                None => emit!(MarketClosed {
+                    market: market.key(),
+                    market_id: market.id,
+                }),
+            }
+        }
+
+        group.resolved = true;
+
+        emit!(GroupResolved {
+            group: group.key(),
+            winning_index,
+        });
+
+        Ok(())
+    }
+
+    /// Turn a NO stake in one group market into YES stakes split evenly across the others
+    pub fn convert_no_position<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ConvertNoPosition<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let group = &ctx.accounts.group;
+        let source = &mut ctx.accounts.market;
+        let user = &ctx.accounts.user;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_BETS)?;
+        require!(!group.resolved, ErrorCode::GroupResolved);
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(!source.paused, ErrorCode::MarketPaused);
+        require!(now < source.end_time, ErrorCode::MarketExpired);
+        require!(ctx.accounts.user_bet.bets[NO] >= amount, ErrorCode::InsufficientPosition);
+
+        let source_key = source.key();
+        let source_index = group.markets.iter().position(|key| *key == source_key)
+            .ok_or(ErrorCode::GroupMarketsMismatch)?;
+        let others = group.markets.len() - 1;
+        require!(others > 0, ErrorCode::GroupTooSmall);
+        // (market, market_vault, user_bet) for every other member, in group order
+        require!(ctx.remaining_accounts.len() == others * 3, ErrorCode::GroupMarketsMismatch);
+
+        // Free the NO stake from the source market
+        source.outcome_pools[NO] -= amount;
+        source.total_pool -= amount;
+        let user_bet = &mut ctx.accounts.user_bet;
+        user_bet.bets[NO] -= amount;
+        user_bet.total_bet -= amount;
+
+        let vault_seeds = &[
+            b"vault",
+            source_key.as_ref(),
+            &[ctx.bumps.market_vault],
+        ];
+        let share = amount / others as u64;
+        let targets = group.markets.iter().enumerate().filter(|(index, _)| *index != source_index);
+
+        for (leg, ((_, key), accounts)) in targets.zip(ctx.remaining_accounts.chunks(3)).enumerate() {
+            require_keys_eq!(accounts[0].key(), *key, ErrorCode::GroupMarketsMismatch);
+            // Rounding dust goes to the last leg
+            let leg_amount = if leg == others - 1 { amount - share * (others as u64 - 1) } else { share };
+
+            let mut market = Account::<Market>::try_from(&accounts[0])?;
+            require!(!market.paused, ErrorCode::MarketPaused);
+            require!(now < market.end_time, ErrorCode::MarketExpired);
+            let vault = Account::<TokenAccount>::try_from(&accounts[1])?;
+            let (vault_address, _) = Pubkey::find_program_address(&[b"vault", key.as_ref()], &crate::ID);
+            require_keys_eq!(vault.key(), vault_address, ErrorCode::InvalidVault);
+
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.market_vault.to_account_info(),
+                        to: vault.to_account_info(),
+                        authority: ctx.accounts.market_vault.to_account_info(),
+                    },
+                    &[&vault_seeds[..]]
+                ),
+                leg_amount
+            )?;
+
+            market.outcome_pools[YES] = market.outcome_pools[YES].checked_add(leg_amount).ok_or(ErrorCode::Overflow)?;
+            market.total_pool = market.total_pool.checked_add(leg_amount).ok_or(ErrorCode::Overflow)?;
+            market.exit(&crate::ID)?;
+
+            let mut target_bet = load_or_create_user_bet(
+                &accounts[2],
+                key,
+                user,
+                &ctx.accounts.system_program,
+            )?;
+            target_bet.bets[YES] = target_bet.bets[YES].checked_add(leg_amount).ok_or(ErrorCode::Overflow)?;
+            target_bet.total_bet = target_bet.total_bet.checked_add(leg_amount).ok_or(ErrorCode::Overflow)?;
+            target_bet.exit(&crate::ID)?;
+        }
+
+        emit!(NoPositionConverted {
+            group: group.key(),
+            market: source_key,
+            user: user.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Create a zero-copy market with up to 64 outcomes - labels live in a separate account
+    pub fn create_large_market(
+        ctx: Context<CreateLargeMarket>,
+        market_id: u64,
+        question: String,
+        outcomes: Vec<String>,
+        end_time: i64,
+        oracle: Pubkey,
+        min_bet: u64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let global_state = &ctx.accounts.global_state;
+        let limits = global_state.limits;
+
+        require_not_paused(global_state, PAUSE_CREATION)?;
+        // No permits or bonds on this variant, so allowlisted mode keeps it to the authority
+        require!(
+            !global_state.permissioned_creation || ctx.accounts.creator.key() == global_state.authority,
+            ErrorCode::CreatorNotPermitted
+        );
+        require!(outcomes.len() >= 2 && outcomes.len() <= MAX_LARGE_OUTCOMES, ErrorCode::InvalidOutcomes);
+        require!(end_time > now && end_time - now >= limits.min_duration, ErrorCode::InvalidEndTime);
+        require!(end_time - now <= limits.max_duration, ErrorCode::EndTimeTooFar);
+        require!(!question.trim().is_empty() && question.len() <= limits.max_question_len as usize, ErrorCode::InvalidQuestion);
+        require!(min_bet > 0, ErrorCode::InvalidMinBet);
+        for outcome in &outcomes {
+            require!(!outcome.trim().is_empty() && outcome.len() <= limits.max_outcome_len as usize, ErrorCode::InvalidOutcome);
+        }
+
+        let mut market = ctx.accounts.market.load_init()?;
+        market.id = market_id;
+        market.creator = ctx.accounts.creator.key();
+        market.oracle = oracle;
+        market.end_time = end_time;
+        market.created_at = now;
+        market.min_bet = min_bet;
+        market.fee_bps = global_state.fee_bps;
+        market.outcome_count = outcomes.len() as u8;
+        market.winner = NO_WINNER;
+        market.bump = ctx.bumps.market;
+        market.version = ACCOUNT_VERSION;
+        market.reserved = [0; 31];
+
+        let labels = &mut ctx.accounts.outcome_labels;
+        labels.market = ctx.accounts.market.key();
+        labels.question = question.clone();
+        labels.outcomes = outcomes.clone();
+        labels.bump = ctx.bumps.outcome_labels;
+        labels.version = ACCOUNT_VERSION;
+        labels.reserved = [0; 32];
+
+        ctx.accounts.global_state.total_markets += 1;
+
+        emit!(LargeMarketCreated {
+            market_id,
+            question,
+            outcomes,
+            end_time,
+            oracle,
+        });
+
+        Ok(())
+    }
+
+    /// Place bet on a large market - pools are updated in place
+    pub fn place_large_bet(
+        ctx: Context<PlaceLargeBet>,
+        outcome_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let market_key = ctx.accounts.market.key();
+        let mut market = ctx.accounts.market.load_mut()?;
+        let now = Clock::get()?.unix_timestamp;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_BETS)?;
+        require!(market.resolved == 0, ErrorCode::MarketResolved);
+        require!(market.paused == 0, ErrorCode::MarketPaused);
+        require!(now < market.end_time, ErrorCode::MarketExpired);
+        require!(outcome_index < market.outcome_count, ErrorCode::InvalidOutcome);
+        require!(amount >= market.min_bet, ErrorCode::BetTooSmall);
+
+        market.total_pool = market.total_pool.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        market.outcome_pools[outcome_index as usize] =
+            market.outcome_pools[outcome_index as usize].checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.market_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                }
+            ),
+            amount
+        )?;
+
+        let user_bet = &mut ctx.accounts.user_bet;
+        if user_bet.user == Pubkey::default() {
+            user_bet.user = ctx.accounts.user.key();
+            user_bet.market = market_key;
+            user_bet.bets = vec![0; market.outcome_count as usize];
+            user_bet.total_bet = 0;
+            user_bet.claimed = false;
+            user_bet.bump = ctx.bumps.user_bet;
+            user_bet.version = ACCOUNT_VERSION;
+            user_bet.open_offers = 0;
+            user_bet.reserved = [0; 30];
+        }
+
+        user_bet.bets[outcome_index as usize] =
+            user_bet.bets[outcome_index as usize].checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        user_bet.total_bet = user_bet.total_bet.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+        emit!(BetPlaced {
+            user: ctx.accounts.user.key(),
+            market: market_key,
+            market_id: market.id,
+            outcome_index,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Resolve large market - oracle or resolver, no dispute window
+    pub fn resolve_large_market(ctx: Context<ResolveLargeMarket>, winning_outcome: u8) -> Result<()> {
+        let mut market = ctx.accounts.market.load_mut()?;
+        let now = Clock::get()?.unix_timestamp;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_RESOLUTION)?;
+        require!(
+            ctx.accounts.resolver.key() == market.oracle ||
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.resolver.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::Resolver,
+            ),
+            ErrorCode::Unauthorized
+        );
+        require!(market.resolved == 0, ErrorCode::AlreadyResolved);
+        require!(now >= market.end_time, ErrorCode::TooEarly);
+        require!(winning_outcome < market.outcome_count, ErrorCode::InvalidOutcome);
+        require!(market.outcome_pools[winning_outcome as usize] > 0, ErrorCode::NoWinners);
+
+        market.resolved = 1;
+        market.winner = winning_outcome;
+        market.resolved_at = now;
+
+        emit!(MarketResolved {
+            market: ctx.accounts.market.key(),
+            market_id: market.id,
+            winner: winning_outcome,
+        });
+
+        Ok(())
+    }
+
+    /// Pause betting on a large market
+    pub fn pause_large_market(ctx: Context<PauseLargeMarket>) -> Result<()> {
+        let mut market = ctx.accounts.market.load_mut()?;
+
+        require!(
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.authority.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::Pauser,
+            ),
+            ErrorCode::Unauthorized
+        );
+        require!(market.resolved == 0, ErrorCode::AlreadyResolved);
+        require!(market.paused == 0, ErrorCode::AlreadyPaused);
+
+        market.paused = 1;
+
+        emit!(MarketPaused {
+            market_id: market.id,
+        });
+
+        Ok(())
+    }
+
+    /// Unpause a large market - resume betting
+    pub fn unpause_large_market(ctx: Context<PauseLargeMarket>) -> Result<()> {
+        let mut market = ctx.accounts.market.load_mut()?;
+
+        require!(
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.authority.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::Pauser,
+            ),
+            ErrorCode::Unauthorized
+        );
+        require!(market.resolved == 0, ErrorCode::AlreadyResolved);
+        require!(market.paused == 1, ErrorCode::MarketNotPaused);
+
+        market.paused = 0;
+
+        emit!(MarketUnpaused {
+            market_id: market.id,
+        });
+
+        Ok(())
+    }
+
+    /// Emergency close large market - bettors claim refunds
+    pub fn close_large_market(ctx: Context<CloseLargeMarket>) -> Result<()> {
+        let mut market = ctx.accounts.market.load_mut()?;
+
+        require!(
+            has_role(
+                &ctx.accounts.global_state,
+                &ctx.accounts.authority.key(),
+                ctx.accounts.role_grant.as_ref(),
+                Role::MarketModerator,
+            ),
+            ErrorCode::Unauthorized
+        );
+        require!(market.resolved == 0, ErrorCode::AlreadyResolved);
+
+        market.resolved = 1;
+        market.winner = NO_WINNER;
+        market.resolved_at = Clock::get()?.unix_timestamp;
+
+        emit!(MarketClosed {
+            market: ctx.accounts.market.key(),
+            market_id: market.id,
+        });
+
+        Ok(())
+    }
+
+    /// Claim winnings from a large market
+    pub fn claim_large_winnings(ctx: Context<ClaimLargeWinnings>) -> Result<()> {
+        let market_key = ctx.accounts.market.key();
+        let market = ctx.accounts.market.load()?;
+        let user_bet = &mut ctx.accounts.user_bet;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
+        require!(market.resolved == 1, ErrorCode::NotResolved);
+        require!(!user_bet.claimed, ErrorCode::AlreadyClaimed);
+        require!(user_bet.user == ctx.accounts.user.key(), ErrorCode::Unauthorized);
+
+        let winner = market.winner().ok_or(ErrorCode::MarketClosed)?;
+        let user_winning_bet = user_bet.bets[winner as usize];
+        require!(user_winning_bet > 0, ErrorCode::NoWinningBet);
+
+        let prize_pool = market.total_pool - market.platform_fee();
+        let user_winnings = winning_payout(user_winning_bet, market.outcome_pools[winner as usize], prize_pool)?;
+        require!(user_winnings >= user_winning_bet, ErrorCode::InvalidPayout);
+
+        let seeds = &[
+            b"vault",
+            market_key.as_ref(),
+            &[ctx.bumps.market_vault],
+        ];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.market_vault.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.market_vault.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            user_winnings
+        )?;
+
+        user_bet.claimed = true;
+
+        emit!(WinningsClaimed {
+            user: ctx.accounts.user.key(),
+            market_id: market.id,
+            amount: user_winnings,
+        });
+
+        Ok(())
+    }
+
+    /// Claim refund from a closed large market
+    pub fn claim_large_refund(ctx: Context<ClaimLargeWinnings>) -> Result<()> {
+        let market_key = ctx.accounts.market.key();
+        let market = ctx.accounts.market.load()?;
+        let user_bet = &mut ctx.accounts.user_bet;
+
+        require_not_paused(&ctx.accounts.global_state, PAUSE_CLAIMS)?;
+        require!(market.resolved == 1, ErrorCode::NotResolved);
+        require!(market.winner().is_none(), ErrorCode::MarketNotClosed);
+        require!(!user_bet.claimed, ErrorCode::AlreadyClaimed);
+        require!(user_bet.user == ctx.accounts.user.key(), ErrorCode::Unauthorized);
+        require!(user_bet.total_bet > 0, ErrorCode::NoRefundAvailable);
+
+        let refund_amount = user_bet.total_bet;
+        let seeds = &[
+            b"vault",
+            market_key.as_ref(),
+            &[ctx.bumps.market_vault],
+        ];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.market_vault.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.market_vault.to_account_info(),
+                },
+                &[&seeds[..]]
+            ),
+            refund_amount
+        )?;
+
+        user_bet.claimed = true;
+
+        emit!(RefundClaimed {
+            user: ctx.accounts.user.key(),
+            market_id: market.id,
+            amount: refund_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Collect platform fees from a large market - once
+    pub fn collect_large_fees(ctx: Context<CollectLargeFees>) -> Result<()> {
+        let market_key = ctx.accounts.market.key();
+        let mut market = ctx.accounts.market.load_mut()?;
+        let global_state = &ctx.accounts.global_state;
+
+        require_not_paused(global_state, PAUSE_CLAIMS)?;
+        require!(
+            has_role(global_state, &ctx.accounts.authority.key(), ctx.accounts.role_grant.as_ref(), Role::FeeManager),
+            ErrorCode::Unauthorized
+        );
+        require!(market.winner().is_some(), ErrorCode::NotResolved);
+        require!(market.fees_collected == 0, ErrorCode::FeesAlreadyCollected);
+
+        let platform_fee = market.platform_fee();
+        market.fees_collected = 1;
+
+        if platform_fee > 0 {
+            let seeds = &[
+                b"vault",
+                market_key.as_ref(),
+                &[ctx.bumps.market_vault],
+            ];
+
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.market_vault.to_account_info(),
+                        to: ctx.accounts.fee_token_account.to_account_info(),
+                        authority: ctx.accounts.market_vault.to_account_info(),
+                    },
+                    &[&seeds[..]]
+                ),
+                platform_fee
+            )?;
+
+            emit!(FeesCollected {
+                market_id: market.id,
+                amount: platform_fee,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Most recent slot hash - unpredictable enough to seed a panel draw
+fn recent_slot_hash(slot_hashes: &AccountInfo) -> Result<[u8; 32]> {
+    let data = slot_hashes.try_borrow_data()?;
+    // Layout: u64 entry count, then (u64 slot, [u8; 32] hash) entries, newest first
+    require!(data.len() >= 48, ErrorCode::InvalidSlotHashes);
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&data[16..48]);
+    Ok(hash)
+}
+
+/// Pick PANEL_SIZE distinct jurors by repeatedly hashing the seed
+fn draw_panel(jurors: &[Pubkey], entropy: &[u8; 32], market: &Pubkey) -> Vec<Pubkey> {
+    let mut panel = Vec::with_capacity(PANEL_SIZE);
+    let mut seed = hashv(&[entropy, market.as_ref()]).to_bytes();
+
+    while panel.len() < PANEL_SIZE {
+        let mut index = [0u8; 8];
+        index.copy_from_slice(&seed[..8]);
+        let candidate = jurors[(u64::from_le_bytes(index) % jurors.len() as u64) as usize];
+        if !panel.contains(&candidate) {
+            panel.push(candidate);
+        }
+        seed = hashv(&[&seed]).to_bytes();
+    }
+
+    panel
+}
+
+/// Accounts create_market and create_market_auto share once the market PDA exists
+struct NewMarket<'a, 'info> {
+    market: &'a mut Account<'info, Market>,
+    market_vault: &'a Account<'info, TokenAccount>,
+    bond_vault: &'a Account<'info, TokenAccount>,
+    global_state: &'a mut Account<'info, GlobalState>,
+    mint: &'a Account<'info, Mint>,
+    oracle_profile: Option<&'a Account<'info, OracleProfile>>,
+    creator_permit: Option<&'a mut Account<'info, CreatorPermit>>,
+    creator_token_account: Option<&'a Account<'info, TokenAccount>>,
+    creator: &'a Signer<'info>,
+    token_program: &'a Program<'info, Token>,
+}
+
+struct MarketParams {
+    question: String,
+    outcomes: Vec<String>,
+    end_time: i64,
+    oracle: Pubkey,
+    min_bet: u64,
+    min_oracle_stake: u64,
+    resolver_fee_bps: u16,
+    resolver_bounty: u64,
+    metadata_uri: String,
+    category: MarketCategory,
+    tags: Vec<String>,
+    rules_hash: [u8; 32],
+}
+
+/// Validate, collect bounty and bond, and fill in a freshly created market
+fn init_market(accounts: NewMarket, market_id: u64, bump: u8, params: MarketParams) -> Result<()> {
+    let MarketParams {
+        question,
+        outcomes,
+        end_time,
+        oracle,
+        min_bet,
+        min_oracle_stake,
+        resolver_fee_bps,
+        resolver_bounty,
+        metadata_uri,
+        category,
+        tags,
+        rules_hash,
+    } = params;
+    let now = Clock::get()?.unix_timestamp;
+    require_not_paused(accounts.global_state, PAUSE_CREATION)?;
+    
+    // Smart validation - not excessive
+    let limits = accounts.global_state.limits;
+    require!(outcomes.len() >= 2 && outcomes.len() <= limits.max_outcomes as usize, ErrorCode::InvalidOutcomes);
+    require!(end_time > now && end_time - now >= limits.min_duration, ErrorCode::InvalidEndTime);
+    require!(end_time - now <= limits.max_duration, ErrorCode::EndTimeTooFar);
+    require!(!question.trim().is_empty() && question.len() <= limits.max_question_len as usize, ErrorCode::InvalidQuestion);
+    require!(min_bet > 0, ErrorCode::InvalidMinBet);
+    require!(oracle != Pubkey::default(), ErrorCode::InvalidOracle);
+    require!(resolver_fee_bps <= MAX_RESOLVER_FEE_BPS, ErrorCode::FeeTooHigh);
+    validate_metadata(&metadata_uri, &tags)?;
+
+    // Validate outcomes - practical checks
+    for outcome in &outcomes {
+        require!(!outcome.trim().is_empty() && outcome.len() <= limits.max_outcome_len as usize, ErrorCode::InvalidOutcome);
+    }
+
+    // An attached profile must stake in this mint, or a lost dispute could never be slashed
+    if let Some(profile) = accounts.oracle_profile {
+        require!(profile.mint == accounts.mint.key(), ErrorCode::InvalidMint);
+    }
+
+    // Optionally hold the oracle to a registered, staked profile
+    if min_oracle_stake > 0 {
+        let profile = accounts.oracle_profile.ok_or(ErrorCode::OracleNotRegistered)?;
+        require!(profile.stake >= min_oracle_stake, ErrorCode::OracleStakeTooLow);
+    }
+
+    // Allowlisted mode - creator needs a permit, within its limits
+    let holds_permit_slot = accounts.global_state.permissioned_creation;
+    if holds_permit_slot {
+        let permit = accounts.creator_permit.ok_or(ErrorCode::CreatorNotPermitted)?;
+        require!(!permit.revoked, ErrorCode::CreatorNotPermitted);
+        require!(
+            permit.allowed_mints.is_empty() || permit.allowed_mints.contains(&accounts.mint.key()),
+            ErrorCode::MintNotAllowed
+        );
+        require!(
+            permit.max_duration == 0 || end_time - now <= permit.max_duration,
+            ErrorCode::DurationNotAllowed
+        );
+        require!(
+            permit.max_open_markets == 0 || permit.open_markets < permit.max_open_markets,
+            ErrorCode::PermitMarketLimit
+        );
+        permit.open_markets = permit.open_markets.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    }
+
+    // Fixed bounty sits in the vault alongside the pool until claimed
+    if resolver_bounty > 0 {
+        let creator_token_account = accounts.creator_token_account
+            .ok_or(ErrorCode::MissingCreatorTokenAccount)?;
+
+        token::transfer(
+            CpiContext::new(
+                accounts.token_program.to_account_info(),
+                Transfer {
+                    from: creator_token_account.to_account_info(),
+                    to: accounts.market_vault.to_account_info(),
+                    authority: accounts.creator.to_account_info(),
+                }
+            ),
+            resolver_bounty
+        )?;
+    }
+
+    // Anti-spam bond, returned once the market resolves cleanly
+    let creation_bond = accounts.global_state.creation_bond;
+    if creation_bond > 0 {
+        let creator_token_account = accounts.creator_token_account
+            .ok_or(ErrorCode::MissingCreatorTokenAccount)?;
+
+        token::transfer(
+            CpiContext::new(
+                accounts.token_program.to_account_info(),
+                Transfer {
+                    from: creator_token_account.to_account_info(),
+                    to: accounts.bond_vault.to_account_info(),
+                    authority: accounts.creator.to_account_info(),
+                }
+            ),
+            creation_bond
+        )?;
+    }
+
+    let market = accounts.market;
+    market.id = market_id;
+    market.creator = accounts.creator.key();
+    market.question = question.clone();
+    market.outcomes = outcomes.clone();
+    market.end_time = end_time;
+    market.oracle = oracle;
+    market.min_bet = min_bet;
+    market.total_pool = 0;
+    market.outcome_pools = vec![0; outcomes.len()];
+    market.resolved = false;
+    market.winner = None;
+    market.paused = false;
+    market.created_at = now;
+    market.resolved_at = 0;
+    market.disputed = false;
+    market.min_oracle_stake = min_oracle_stake;
+    market.resolved_by = Pubkey::default();
+    market.resolver_fee_bps = resolver_fee_bps;
+    market.resolver_bounty = resolver_bounty;
+    market.resolver_paid = false;
+    market.creation_bond = creation_bond;
+    market.bond_forfeited = false;
+    market.bond_settled = false;
+    market.holds_permit_slot = holds_permit_slot;
+    market.metadata_uri = metadata_uri.clone();
+    market.category = category;
+    market.tags = tags.clone();
+    market.rules_hash = rules_hash;
+    market.fee_bps = accounts.global_state.fee_bps; // Later fee changes don't touch open markets
+    market.bump = bump;
+    market.version = ACCOUNT_VERSION;
+    market.prev_market = Pubkey::default();
+    market.in_group = false;
+    market.fees_collected = false;
+    market.dispute_window = accounts.global_state.dispute_window; // Like fees, fixed for the market's life
+    market.reserved = [0; 22];
+
+    // Update global counter
+    accounts.global_state.total_markets += 1;
+
+    emit!(MarketCreated {
+        market: market.key(),
+        market_id,
+        question,
+        outcomes,
+        end_time,
+        oracle,
+        metadata_uri,
+        category,
+        tags,
+        rules_hash,
+    });
+
+    Ok(())
+}
+
+fn validate_metadata(metadata_uri: &str, tags: &[String]) -> Result<()> {
+    require!(metadata_uri.len() <= MAX_URI_LEN, ErrorCode::InvalidMetadataUri);
+    require!(tags.len() <= MAX_TAGS, ErrorCode::InvalidTags);
+    for tag in tags {
+        require!(!tag.trim().is_empty() && tag.len() <= MAX_TAG_LEN, ErrorCode::InvalidTags);
+    }
+    Ok(())
+}
+
+fn require_not_paused(state: &GlobalState, op: u8) -> Result<()> {
+    require!(state.paused_ops & op == 0, ErrorCode::PlatformPaused);
+    Ok(())
+}
+
+/// Authority holds every role; anyone else needs a matching grant
+fn has_role(state: &GlobalState, signer: &Pubkey, grant: Option<&Account<RoleGrant>>, role: Role) -> bool {
+    *signer == state.authority
+        || grant.is_some_and(|grant| grant.grantee == *signer && grant.role == role)
+}
+
+/// Check a queued change matches and has matured; the context closes it
+fn consume_change(pending: &PendingChange, expected: &ConfigChange) -> Result<()> {
+    require!(pending.change == *expected, ErrorCode::ChangeMismatch);
+    require!(Clock::get()?.unix_timestamp >= pending.eta, ErrorCode::TimelockActive);
+    Ok(())
+}
+
+/// Hold a market's oracle to its stake requirement and add the resolution to its track record
+fn record_oracle_resolution(market: &Market, profile: Option<&mut Account<OracleProfile>>, now: i64) -> Result<()> {
+    if market.min_oracle_stake > 0 {
+        let profile = profile.as_ref().ok_or(ErrorCode::OracleNotRegistered)?;
+        require!(profile.stake >= market.min_oracle_stake, ErrorCode::OracleStakeTooLow);
+        // An unbonding oracle could walk away before a dispute lands
+        require!(profile.unstake_requested_at == 0, ErrorCode::OracleUnbonding);
+    }
+
+    // Registered oracles build a track record
+    if let Some(profile) = profile {
+        let latency = now.saturating_sub(market.end_time);
+        profile.markets_resolved = profile.markets_resolved.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        profile.avg_latency = ((profile.avg_latency as i128 * (profile.markets_resolved - 1) as i128
+            + latency as i128) / profile.markets_resolved as i128) as i64;
+    }
+
+    Ok(())
+}
+
+/// Free the creator's open market slot once a permitted market is settled
+fn release_permit_slot(market: &mut Market, permit: Option<&mut Account<CreatorPermit>>) -> Result<()> {
+    if market.holds_permit_slot {
+        let permit = permit.ok_or(ErrorCode::PermitRequired)?;
+        permit.open_markets = permit.open_markets.saturating_sub(1);
+        market.holds_permit_slot = false;
+    }
+    Ok(())
+}
+
+/// Platform and resolver cuts of a resolved market's pool
+fn settlement_fees(market: &Market) -> Result<(u64, u64)> {
+    let platform_fee = (market.total_pool as u128 * market.fee_bps as u128 / 10000) as u64;
+    // Overturned resolutions clear resolved_by and forfeit the resolver cut
+    let resolver_fee = if market.resolved_by == Pubkey::default() {
+        0
+    } else {
+        (market.total_pool as u128 * market.resolver_fee_bps as u128 / 10000) as u64
+    };
+
+    require!(platform_fee + resolver_fee < market.total_pool, ErrorCode::FeeExceedsPool);
+
+    Ok((platform_fee, resolver_fee))
+}
+
+/// Accounts with a layout version, upgradable in place from their layout before versioning
+trait Versioned: AccountSerialize + AccountDeserialize + anchor_lang::Discriminator {
+    /// Layout as deployed before versioning, without the discriminator
+    type Legacy: AnchorDeserialize;
+
+    fn version(&self) -> u8;
+
+    /// Size needed for the current layout and contents
+    fn required_space(&self) -> usize;
+}
+
+impl Versioned for GlobalState {
+    type Legacy = LegacyGlobalState;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn required_space(&self) -> usize {
+        8 + Self::INIT_SPACE
+    }
+}
+
+impl Versioned for Market {
+    type Legacy = LegacyMarket;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn required_space(&self) -> usize {
+        Market::space(&self.question, &self.outcomes, &self.metadata_uri, &self.tags)
+    }
+}
+
+impl Versioned for UserBet {
+    type Legacy = LegacyUserBet;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn required_space(&self) -> usize {
+        UserBet::space(self.bets.len())
+    }
+}
+
+/// Rewrite a pre-versioning account in the current layout, growing it if needed
+fn migrate_account<'info, T: Versioned>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    upgrade: impl FnOnce(T::Legacy) -> T,
+) -> Result<()> {
+    let data = account.try_borrow_data()?.to_vec();
+
+    // A legacy account either fails to read as the current layout or reads its zero padding as version 0
+    if let Ok(current) = T::try_deserialize(&mut &data[..]) {
+        require!(current.version() < ACCOUNT_VERSION, ErrorCode::AlreadyMigrated);
+    }
+    require!(
+        data.len() >= 8 && data[..8] == T::DISCRIMINATOR,
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+    );
+    let legacy = T::Legacy::deserialize(&mut &data[8..])
+        .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+    let state = upgrade(legacy);
+
+    // Never shrink - the original payer funded any slack
+    let new_len = state.required_space().max(account.data_len());
+    if new_len > account.data_len() {
+        let top_up = Rent::get()?.minimum_balance(new_len).saturating_sub(account.lamports());
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: payer.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        account.realloc(new_len, true)?;
+    }
+
+    state.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+/// Apply `update` to each open member market, passed as writable remaining accounts in event order
+fn for_each_member<'info>(
+    event: &MarketEvent,
+    members: &'info [AccountInfo<'info>],
+    mut update: impl FnMut(&mut Account<'info, Market>) -> Result<()>,
+) -> Result<()> {
+    require!(members.len() == event.markets.len(), ErrorCode::EventMarketsMismatch);
+
+    for (info, key) in members.iter().zip(&event.markets) {
+        require_keys_eq!(info.key(), *key, ErrorCode::EventMarketsMismatch);
+
+        let mut market = Account::<Market>::try_from(info)?;
+        // Settled markets keep their outcome
+        if !market.resolved {
+            update(&mut market)?;
+            market.exit(&crate::ID)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Load the user's bet on a binary market, creating the PDA if this is their first position
+fn load_or_create_user_bet<'info>(
+    info: &'info AccountInfo<'info>,
+    market: &Pubkey,
+    user: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<Account<'info, UserBet>> {
+    let user_key = user.key();
+    let (address, bump) = Pubkey::find_program_address(&[b"bet", user_key.as_ref(), market.as_ref()], &crate::ID);
+    require_keys_eq!(info.key(), address, ErrorCode::InvalidUserBet);
+
+    if !info.data_is_empty() {
+        let user_bet = Account::<UserBet>::try_from(info)?;
+        require_keys_eq!(user_bet.user, user_key, ErrorCode::Unauthorized);
+        return Ok(user_bet);
+    }
+
+    let space = UserBet::space(2);
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::CreateAccount {
+                from: user.to_account_info(),
+                to: info.clone(),
+            },
+            &[&[b"bet", user_key.as_ref(), market.as_ref(), &[bump]]],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )?;
+
+    let mut user_bet = Account::<UserBet>::try_from_unchecked(info)?;
+    user_bet.user = user_key;
+    user_bet.market = *market;
+    user_bet.bets = vec![0; 2];
+    user_bet.total_bet = 0;
+    user_bet.claimed = false;
+    user_bet.bump = bump;
+    user_bet.version = ACCOUNT_VERSION;
+    user_bet.open_offers = 0;
+    user_bet.reserved = [0; 30];
+
+    Ok(user_bet)
+}
+
+/// One outcome and stake in a `place_bets` call
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BetLeg {
+    pub outcome_index: u8,
+    pub amount: u64,
+}
+
+/// What a user signs off-chain to have a relayer place a bet for them
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct BetIntent {
+    pub program: Pubkey,             // Binds the intent to this deployment
+    pub relayer: Pubkey,             // Only this relayer may submit it
+    pub market: Pubkey,
+    pub outcome_index: u8,
+    pub amount: u64,
+    pub relayer_fee: u64,
+    pub nonce: u64,
+    pub expires_at: i64,
+}
+
+/// Require that the previous instruction is an Ed25519 check of `signer` signing `message`
+fn verify_intent_signature(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current = ix_sysvar::load_current_index_checked(instructions)?;
+    require!(current > 0, ErrorCode::InvalidSignature);
+    let ix = ix_sysvar::load_instruction_at_checked(current as usize - 1, instructions)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, ErrorCode::InvalidSignature);
+
+    // One signature whose key, signature and message all live in this instruction's data
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, ErrorCode::InvalidSignature);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    require!(
+        read_u16(4) == u16::MAX && read_u16(8) == u16::MAX && read_u16(14) == u16::MAX,
+        ErrorCode::InvalidSignature
+    );
+
+    let key_at = read_u16(6) as usize;
+    let message_at = read_u16(10) as usize;
+    let message_len = read_u16(12) as usize;
+    require!(
+        data.get(key_at..key_at + 32) == Some(signer.as_ref()) &&
+        data.get(message_at..message_at + message_len) == Some(message),
+        ErrorCode::InvalidSignature
+    );
+    Ok(())
+}
+
+/// Give a receipt mint Metaplex metadata and a master edition so wallets show it as an NFT.
+/// The receipt PDA is the update authority and the metadata is immutable.
+fn create_receipt_nft(accounts: &PlaceBetWithReceipt, name: String, uri: String, receipt_bump: u8) -> Result<()> {
+    let mint = accounts.receipt_mint.key();
+    let user = accounts.user.key();
+    let receipt = accounts.receipt.key();
+    let seeds: &[&[u8]] = &[b"receipt", mint.as_ref(), &[receipt_bump]];
+    let account_infos = [
+        accounts.receipt_metadata.to_account_info(),
+        accounts.receipt_edition.to_account_info(),
+        accounts.receipt_mint.to_account_info(),
+        accounts.user.to_account_info(),
+        accounts.receipt.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.system_program.to_account_info(),
+        accounts.token_metadata_program.to_account_info(),
+    ];
+
+    // CreateMetadataAccountV3: no creators, collection or uses, immutable, no collection details
+    let mut data = vec![33];
+    (name, RECEIPT_SYMBOL.to_string(), uri, 0u16).serialize(&mut data)?;
+    data.extend_from_slice(&[0, 0, 0, 0, 0]);
+    invoke_signed(
+        &Instruction {
+            program_id: token_metadata::ID,
+            accounts: vec![
+                AccountMeta::new(accounts.receipt_metadata.key(), false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(user, true),
+                AccountMeta::new(user, true),
+                AccountMeta::new_readonly(receipt, true),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data,
+        },
+        &account_infos,
+        &[seeds],
+    )?;
+
+    // CreateMasterEditionV3 with a max supply of zero - takes over the mint authority
+    let mut data = vec![17];
+    Some(0u64).serialize(&mut data)?;
+    invoke_signed(
+        &Instruction {
+            program_id: token_metadata::ID,
+            accounts: vec![
+                AccountMeta::new(accounts.receipt_edition.key(), false),
+                AccountMeta::new(mint, false),
+                AccountMeta::new_readonly(receipt, true),
+                AccountMeta::new_readonly(user, true),
+                AccountMeta::new(user, true),
+                AccountMeta::new(accounts.receipt_metadata.key(), false),
+                AccountMeta::new_readonly(token::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data,
+        },
+        &account_infos,
+        &[seeds],
+    )?;
+    Ok(())
+}
+
+/// The user's current approval for their spend authority, or None if the token account
+/// approves someone else or nobody
+fn spend_allowance(token_account: &TokenAccount, spend_authority: &AccountInfo) -> Option<u64> {
+    (token_account.delegate == COption::Some(spend_authority.key())).then_some(token_account.delegated_amount)
+}
+
+/// Checks shared by every way of betting on a regular market
+fn check_bet(market: &Market, outcome_index: u8, amount: u64, now: i64) -> Result<()> {
+    require!(!market.resolved, ErrorCode::MarketResolved);
+    require!(!market.paused, ErrorCode::MarketPaused);
+    require!(now < market.end_time, ErrorCode::MarketExpired);
+    require!(outcome_index < market.outcomes.len() as u8, ErrorCode::InvalidOutcome);
+    require!(amount >= market.min_bet, ErrorCode::BetTooSmall);
+    Ok(())
+}
+
+fn add_to_pools(market: &mut Market, outcome_index: u8, amount: u64) -> Result<()> {
+    market.total_pool = market.total_pool.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    market.outcome_pools[outcome_index as usize] =
+        market.outcome_pools[outcome_index as usize].checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    Ok(())
+}
+
+/// Add a stake to the user's bet, filling in a freshly created account first
+fn credit_user_bet(
+    user_bet: &mut UserBet,
+    market: &Account<Market>,
+    user: Pubkey,
+    bump: u8,
+    outcome_index: u8,
+    amount: u64,
+) -> Result<()> {
+    if user_bet.user == Pubkey::default() {
+        user_bet.user = user;
+        user_bet.market = market.key();
+        user_bet.bets = vec![0; market.outcomes.len()];
+        user_bet.total_bet = 0;
+        user_bet.claimed = false;
+        user_bet.bump = bump;
+        user_bet.version = ACCOUNT_VERSION;
+        user_bet.open_offers = 0;
+        user_bet.reserved = [0; 30];
+    }
+
+    user_bet.bets[outcome_index as usize] =
+        user_bet.bets[outcome_index as usize].checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    user_bet.total_bet = user_bet.total_bet.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    Ok(())
+}
+
+/// Pro-rata share of the prize pool for a winning stake
+fn winning_payout(stake: u64, winning_pool: u64, prize_pool: u64) -> Result<u64> {
+    // Use checked arithmetic and ensure no precision loss
+    Ok((stake as u128)
+        .checked_mul(prize_pool as u128)
+        .ok_or(ErrorCode::Overflow)?
+        .checked_div(winning_pool as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64)
+}
+
+/// Checks shared by claim_winnings, claim_refund and claim_many - returns what the position is owed
+fn claim_payout(market: &Market, user_bet: &UserBet, now: i64) -> Result<u64> {
+    require!(market.resolved, ErrorCode::NotResolved);
+    require!(!user_bet.claimed, ErrorCode::AlreadyClaimed);
+    require!(user_bet.open_offers == 0, ErrorCode::OffersOpen);
+
+    match market.winner {
+        Some(winner) => {
+            require!(!market.disputed, ErrorCode::MarketDisputed);
+            require!(now >= dispute_deadline(market), ErrorCode::DisputeWindowOpen);
+
+            let stake = user_bet.bets[winner as usize];
+            require!(stake > 0, ErrorCode::NoWinningBet);
+
+            // Calculate payout - clean math with overflow protection
+            let (platform_fee, resolver_fee) = settlement_fees(market)?;
+            let prize_pool = market.total_pool - platform_fee - resolver_fee;
+            let winnings = winning_payout(stake, market.outcome_pools[winner as usize], prize_pool)?;
+            require!(winnings >= stake, ErrorCode::InvalidPayout);
+            Ok(winnings)
+        }
+        None => {
+            require!(user_bet.total_bet > 0, ErrorCode::NoRefundAvailable);
+            refund_payout(user_bet.total_bet, market)
+        }
+    }
+}
+
+/// When a resolution can no longer be challenged - markets from before the window was configurable use the default
+fn dispute_deadline(market: &Market) -> i64 {
+    let window = if market.dispute_window > 0 { market.dispute_window } else { DISPUTE_WINDOW };
+    market.resolved_at + window
+}
+
+/// Refund for a stake in a market with no winner - pro-rata, so an oracle slash paid into the pool reaches bettors
+fn refund_payout(stake: u64, market: &Market) -> Result<u64> {
+    let bet_pool = market.outcome_pools.iter()
+        .try_fold(0u64, |sum, pool| sum.checked_add(*pool))
+        .ok_or(ErrorCode::Overflow)?;
+    winning_payout(stake, bet_pool, market.total_pool)
+}
+
+fn vote_commitment(outcome: u8, salt: &[u8; 32], juror: &Pubkey) -> [u8; 32] {
+    hashv(&[&[outcome], salt, juror.as_ref()]).to_bytes()
+}
+
+// Clean, efficient account structures - sized by InitSpace
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalState {
+    pub authority: Pubkey,
+    pub fee_bps: u16,
+    pub fee_recipient: Pubkey,
+    pub total_markets: u64,
+    pub bump: u8,
+    pub creation_bond: u64,
+    pub permissioned_creation: bool,
+    pub pending_authority: Pubkey,
+    pub change_nonce: u64,
+    pub paused_ops: u8,              // PAUSE_* flags
+    pub limits: PlatformLimits,
+    pub version: u8,
+    pub dispute_window: i64,         // Copied into each new market
+    pub reserved: [u8; 56],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct PlatformLimits {
+    pub max_outcomes: u8,
+    pub max_question_len: u16,
+    pub max_outcome_len: u16,
+    pub max_fee_bps: u16,
+    pub min_duration: i64,
+    pub max_duration: i64,
+}
+
+impl Default for PlatformLimits {
+    fn default() -> Self {
+        Self {
+            max_outcomes: DEFAULT_MAX_OUTCOMES,
+            max_question_len: DEFAULT_MAX_QUESTION_LEN,
+            max_outcome_len: DEFAULT_MAX_OUTCOME_LEN,
+            max_fee_bps: DEFAULT_MAX_FEE_BPS,
+            min_duration: DEFAULT_MIN_DURATION,
+            max_duration: DEFAULT_MAX_DURATION,
+        }
+    }
+}
+
+// Variable-length fields count as empty here - Market::space adds their contents
+#[account]
+#[derive(InitSpace)]
+pub struct Market {
+    pub id: u64,
+    pub creator: Pubkey,
+    #[max_len(0)]
+    pub question: String,
+    #[max_len(0, 0)]
+    pub outcomes: Vec<String>,
+    pub end_time: i64,
+    pub oracle: Pubkey,
+    pub min_bet: u64,
+    pub total_pool: u64,
+    #[max_len(0)]
+    pub outcome_pools: Vec<u64>,
+    pub resolved: bool,
+    pub winner: Option<u8>,
+    pub paused: bool,
+    pub created_at: i64,
+    pub bump: u8,
+    pub resolved_at: i64,
+    pub disputed: bool,
+    pub min_oracle_stake: u64,
+    pub resolved_by: Pubkey,
+    pub resolver_fee_bps: u16,
+    pub resolver_bounty: u64,
+    pub resolver_paid: bool,
+    pub creation_bond: u64,
+    pub bond_forfeited: bool,
+    pub bond_settled: bool,
+    pub holds_permit_slot: bool,
+    #[max_len(0)]
+    pub metadata_uri: String,
+    pub category: MarketCategory,
+    #[max_len(0, 0)]
+    pub tags: Vec<String>,
+    pub rules_hash: [u8; 32],        // sha256 of the full resolution rules
+    pub fee_bps: u16,                // Platform fee at creation
+    pub version: u8,
+    pub prev_market: Pubkey,         // Previous market in its series, if any
+    pub in_group: bool,              // Settled only through its ExclusiveGroup
+    pub fees_collected: bool,
+    pub dispute_window: i64,         // Platform window at creation - zero means DISPUTE_WINDOW
+    pub reserved: [u8; 22],
+}
+
+impl Market {
+    /// Exact account size for the given contents
+    pub fn space(question: &str, outcomes: &[String], metadata_uri: &str, tags: &[String]) -> usize {
+        8 + Self::INIT_SPACE
+            + question.len()
+            + outcomes.iter().map(|outcome| 4 + outcome.len()).sum::<usize>()
+            + 8 * outcomes.len()
+            + metadata_uri.len()
+            + tags.iter().map(|tag| 4 + tag.len()).sum::<usize>()
+    }
+}
+
+// Zero-copy market for up to 64 outcomes - fields ordered to avoid padding
+#[account(zero_copy)]
+#[derive(InitSpace)]
+pub struct LargeMarket {
+    pub outcome_pools: [u64; MAX_LARGE_OUTCOMES],
+    pub id: u64,
+    pub end_time: i64,
+    pub created_at: i64,
+    pub resolved_at: i64,
+    pub min_bet: u64,
+    pub total_pool: u64,
+    pub creator: Pubkey,
+    pub oracle: Pubkey,
+    pub fee_bps: u16,                // Platform fee at creation
+    pub outcome_count: u8,
+    pub resolved: u8,
+    pub winner: u8,                  // NO_WINNER until resolved, or if closed
+    pub paused: u8,
+    pub fees_collected: u8,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 31],
+}
+
+impl LargeMarket {
+    pub fn winner(&self) -> Option<u8> {
+        (self.resolved == 1 && self.winner != NO_WINNER).then_some(self.winner)
+    }
+
+    pub fn platform_fee(&self) -> u64 {
+        (self.total_pool as u128 * self.fee_bps as u128 / 10000) as u64
+    }
+}
+
+/// Question and outcome labels for a LargeMarket - never read while betting
+#[account]
+#[derive(InitSpace)]
+pub struct OutcomeLabels {
+    pub market: Pubkey,
+    #[max_len(0)]
+    pub question: String,
+    #[max_len(0, 0)]
+    pub outcomes: Vec<String>,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl OutcomeLabels {
+    pub fn space(question: &str, outcomes: &[String]) -> usize {
+        8 + Self::INIT_SPACE
+            + question.len()
+            + outcomes.iter().map(|outcome| 4 + outcome.len()).sum::<usize>()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum MarketCategory {
+    Crypto,
+    Sports,
+    Politics,
+    Economics,
+    Entertainment,
+    Science,
+    Other,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserBet {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    #[max_len(0)]
+    pub bets: Vec<u64>,              // One slot per market outcome
+    pub total_bet: u64,
+    pub claimed: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub open_offers: u16,            // Offers escrowing part of this position
+    pub reserved: [u8; 30],
+}
+
+impl UserBet {
+    /// Bets are sized to the market's outcome count, which never changes
+    pub fn space(outcomes: usize) -> usize {
+        8 + Self::INIT_SPACE + 8 * outcomes
+    }
+}
+
+/// A session key allowed to bet for a user until it expires or hits its cap
+#[account]
+#[derive(InitSpace)]
+pub struct Delegate {
+    pub user: Pubkey,
+    pub session_key: Pubkey,
+    pub expires_at: i64,
+    pub spending_cap: u64,
+    pub spent: u64,
+    #[max_len(MAX_DELEGATE_MARKETS)]
+    pub markets: Vec<Pubkey>,        // Empty means any market
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+/// One per user - relayed intents must use nonces at or above `next_nonce` and stay within the budget
+#[account]
+#[derive(InitSpace)]
+pub struct RelayNonce {
+    pub next_nonce: u64,
+    pub spending_cap: u64,           // Set by the user with set_relay_budget
+    pub spent: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 8],
+}
+
+/// Attributes of a bet held as a receipt token - paid to whoever holds the token
+#[account]
+#[derive(InitSpace)]
+pub struct PositionReceipt {
+    pub market: Pubkey,
+    pub mint: Pubkey,                // 0-decimal mint with a supply of one
+    pub outcome_index: u8,
+    pub amount: u64,
+    pub placed_at: i64,
+    pub claimed: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+/// A slice of one outcome's stake escrowed for sale at a fixed price
+#[account]
+#[derive(InitSpace)]
+pub struct Offer {
+    pub seller: Pubkey,
+    pub market: Pubkey,
+    pub mint: Pubkey,                // Collateral mint the price is paid in
+    pub offer_id: u64,
+    pub outcome_index: u8,
+    pub amount: u64,
+    pub price: u64,
+    pub expires_at: i64,             // Fillable until this time, never past the market's end
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 24],
+}
+
+/// Binary markets of which exactly one resolves YES, e.g. one per election candidate
+#[account]
+#[derive(InitSpace)]
+pub struct ExclusiveGroup {
+    pub creator: Pubkey,
+    pub group_id: u64,
+    pub oracle: Pubkey,
+    pub mint: Pubkey,                // Collateral every member market uses
+    #[max_len(MAX_GROUP_MARKETS)]
+    pub markets: Vec<Pubkey>,
+    pub resolved: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+/// Related markets sharing an end time and oracle, e.g. all props for one match
+#[account]
+#[derive(InitSpace)]
+pub struct MarketEvent {
+    pub creator: Pubkey,
+    pub event_id: u64,
+    #[max_len(0)]
+    pub title: String,
+    #[max_len(0)]
+    pub metadata_uri: String,
+    pub end_time: i64,
+    pub oracle: Pubkey,
+    #[max_len(MAX_EVENT_MARKETS)]
+    pub markets: Vec<Pubkey>,
+    pub paused: bool,
+    pub closed: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl MarketEvent {
+    pub fn space(title: &str, metadata_uri: &str) -> usize {
+        8 + Self::INIT_SPACE + title.len() + metadata_uri.len()
+    }
+}
+
+/// Marks a market as belonging to an event - derive it from the market to find the event
+#[account]
+#[derive(InitSpace)]
+pub struct EventMembership {
+    pub event: Pubkey,
+    pub market: Pubkey,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+/// Template for a recurring market, e.g. one per day
+#[account]
+#[derive(InitSpace)]
+pub struct MarketSeries {
+    pub creator: Pubkey,
+    pub series_id: u64,
+    #[max_len(0)]
+    pub question: String,
+    #[max_len(0, 0)]
+    pub outcomes: Vec<String>,
+    pub oracle: Pubkey,
+    pub mint: Pubkey,
+    pub duration: i64,
+    pub min_bet: u64,
+    pub category: MarketCategory,
+    pub rules_hash: [u8; 32],
+    pub next_index: u64,             // Index of the next market to open
+    pub current_market: Pubkey,      // Latest market, default until the first roll
+    pub last_end_time: i64,          // Latest market's end time
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl MarketSeries {
+    pub fn space(question: &str, outcomes: &[String]) -> usize {
+        8 + Self::INIT_SPACE
+            + question.len()
+            + outcomes.iter().map(|outcome| 4 + outcome.len()).sum::<usize>()
+    }
+}
+
+/// Per-creator sequence behind create_market_auto
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorCounter {
+    pub creator: Pubkey,
+    pub next_id: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RoleGrant {
+    pub grantee: Pubkey,
+    pub role: Role,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum Role {
+    Pauser,
+    Resolver,
+    FeeManager,
+    MarketModerator,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PendingChange {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub eta: i64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum ConfigChange {
+    Config { fee_bps: Option<u16>, fee_recipient: Option<Pubkey> },
+    Authority { new_authority: Pubkey },
+    Oracle { market: Pubkey, new_oracle: Pubkey },
+    MaxFee { max_fee_bps: u16 },
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorPermit {
+    pub creator: Pubkey,
+    pub max_open_markets: u32,       // 0 = unlimited
+    #[max_len(MAX_PERMIT_MINTS)]
+    pub allowed_mints: Vec<Pubkey>,  // Empty = any
+    pub max_duration: i64,           // 0 = platform max
+    pub open_markets: u32,
+    pub revoked: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct JurorRegistry {
+    pub mint: Pubkey,
+    pub min_stake: u64,
+    pub slash_bps: u16,
+    pub commit_period: i64,
+    pub reveal_period: i64,
+    pub dispute_bond: u64,           // Posted by each challenger, in the registry mint
+    #[max_len(MAX_JURORS)]
+    pub jurors: Vec<JurorEntry>,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct JurorEntry {
+    pub juror: Pubkey,
+    pub active_disputes: u16,        // Panels drawn onto and not yet settled
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Juror {
+    pub juror: Pubkey,
+    pub stake: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct OracleProfile {
+    pub oracle: Pubkey,
+    pub mint: Pubkey,
+    pub stake: u64,
+    pub markets_resolved: u64,
+    pub disputes_lost: u64,
+    pub avg_latency: i64,
+    pub unstake_requested_at: i64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute {
+    pub market: Pubkey,
+    pub challenger: Pubkey,
+    pub original_winner: u8,
+    #[max_len(PANEL_SIZE)]
+    pub panel: Vec<Pubkey>,
+    #[max_len(PANEL_SIZE)]
+    pub commitments: Vec<[u8; 32]>,
+    #[max_len(PANEL_SIZE)]
+    pub votes: Vec<Option<u8>>,
+    #[max_len(PANEL_SIZE)]
+    pub settled: Vec<bool>,
+    pub commit_deadline: i64,
+    pub reveal_deadline: i64,
+    pub finalized: bool,
+    pub final_outcome: Option<u8>,
+    pub bond: u64,
+    pub bond_settled: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+// Layouts deployed before versioning - read only by the migrate_* instructions
+#[derive(AnchorDeserialize)]
+pub struct LegacyGlobalState {
+    pub authority: Pubkey,
+    pub fee_bps: u16,
+    pub fee_recipient: Pubkey,
+    pub total_markets: u64,
+    pub bump: u8,
+}
+
+impl LegacyGlobalState {
+    fn upgrade(self) -> GlobalState {
+        GlobalState {
+            authority: self.authority,
+            fee_bps: self.fee_bps,
+            fee_recipient: self.fee_recipient,
+            total_markets: self.total_markets,
+            bump: self.bump,
+            creation_bond: 0,
+            permissioned_creation: false,
+            pending_authority: Pubkey::default(),
+            change_nonce: 0,
+            paused_ops: 0,
+            limits: PlatformLimits::default(),
+            version: ACCOUNT_VERSION,
+            dispute_window: DISPUTE_WINDOW,
+            reserved: [0; 56],
+        }
+    }
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyMarket {
+    pub id: u64,
+    pub creator: Pubkey,
+    pub question: String,
+    pub outcomes: Vec<String>,
+    pub end_time: i64,
+    pub oracle: Pubkey,
+    pub min_bet: u64,
+    pub total_pool: u64,
+    pub outcome_pools: Vec<u64>,
+    pub resolved: bool,
+    pub winner: Option<u8>,
+    pub paused: bool,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl LegacyMarket {
+    /// Legacy markets charged the platform's current fee at claim time - `fee_bps` pins it
+    fn upgrade(self, fee_bps: u16) -> Market {
+        Market {
+            id: self.id,
+            creator: self.creator,
+            question: self.question,
+            outcomes: self.outcomes,
+            end_time: self.end_time,
+            oracle: self.oracle,
+            min_bet: self.min_bet,
+            total_pool: self.total_pool,
+            outcome_pools: self.outcome_pools,
+            resolved: self.resolved,
+            winner: self.winner,
+            paused: self.paused,
+            created_at: self.created_at,
+            bump: self.bump,
+            resolved_at: 0, // Legacy resolutions are past any dispute window
+            disputed: false,
+            min_oracle_stake: 0,
+            resolved_by: Pubkey::default(),
+            resolver_fee_bps: 0,
+            resolver_bounty: 0,
+            resolver_paid: false,
+            creation_bond: 0,
+            bond_forfeited: false,
+            bond_settled: true, // No bond vault to settle
+            holds_permit_slot: false,
+            metadata_uri: String::new(),
+            category: MarketCategory::Other,
+            tags: Vec::new(),
+            rules_hash: [0; 32],
+            fee_bps,
+            version: ACCOUNT_VERSION,
+            prev_market: Pubkey::default(),
+            in_group: false,
+            // Legacy collect_fees kept no record, so a resolved market may already have paid out
+            fees_collected: self.resolved,
+            dispute_window: DISPUTE_WINDOW,
+            reserved: [0; 22],
+        }
+    }
+}
+
+#[derive(AnchorDeserialize)]
+pub struct LegacyUserBet {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub bets: Vec<u64>,
+    pub total_bet: u64,
+    pub claimed: bool,
+    pub bump: u8,
+}
+
+impl LegacyUserBet {
+    fn upgrade(self) -> UserBet {
+        UserBet {
+            user: self.user,
+            market: self.market,
+            bets: self.bets,
+            total_bet: self.total_bet,
+            claimed: self.claimed,
+            bump: self.bump,
+            version: ACCOUNT_VERSION,
+            open_offers: 0,
+            reserved: [0; 30],
+        }
+    }
+}
+
+// Account contexts - practical constraints
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GlobalState::INIT_SPACE,
+        seeds = [b"global"],
+        bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    market_id: u64,
+    question: String,
+    outcomes: Vec<String>,
+    end_time: i64,
+    oracle: Pubkey,
+    min_bet: u64,
+    min_oracle_stake: u64,
+    resolver_fee_bps: u16,
+    resolver_bounty: u64,
+    metadata_uri: String,
+    category: MarketCategory,
+    tags: Vec<String>,
+)]
+pub struct CreateMarket<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = Market::space(&question, &outcomes, &metadata_uri, &tags),
+        seeds = [b"market", market_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"vault", market.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = market_vault,
+    )]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"bond", market.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = bond_vault,
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub global_state: Account<'info, GlobalState>,
+    pub mint: Account<'info, Mint>,
+    #[account(seeds = [b"oracle", oracle.as_ref()], bump = oracle_profile.bump)]
+    pub oracle_profile: Option<Account<'info, OracleProfile>>,
+    #[account(mut, seeds = [b"permit", creator.key().as_ref()], bump = creator_permit.bump)]
+    pub creator_permit: Option<Account<'info, CreatorPermit>>,
+    #[account(mut)]
+    pub creator_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> CreateMarket<'info> {
+    fn new_market(&mut self) -> NewMarket<'_, 'info> {
+        NewMarket {
+            market: &mut self.market,
+            market_vault: &self.market_vault,
+            bond_vault: &self.bond_vault,
+            global_state: &mut self.global_state,
+            mint: &self.mint,
+            oracle_profile: self.oracle_profile.as_ref(),
+            creator_permit: self.creator_permit.as_mut(),
+            creator_token_account: self.creator_token_account.as_ref(),
+            creator: &self.creator,
+            token_program: &self.token_program,
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(
+    question: String,
+    outcomes: Vec<String>,
+    end_time: i64,
+    oracle: Pubkey,
+    min_bet: u64,
+    min_oracle_stake: u64,
+    resolver_fee_bps: u16,
+    resolver_bounty: u64,
+    metadata_uri: String,
+    category: MarketCategory,
+    tags: Vec<String>,
+)]
+pub struct CreateMarketAuto<'info> {
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + CreatorCounter::INIT_SPACE,
+        seeds = [b"market_counter", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_counter: Account<'info, CreatorCounter>,
+    #[account(
+        init,
+        payer = creator,
+        space = Market::space(&question, &outcomes, &metadata_uri, &tags),
+        seeds = [b"market", creator.key().as_ref(), creator_counter.next_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"vault", market.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = market_vault,
+    )]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"bond", market.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = bond_vault,
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub global_state: Account<'info, GlobalState>,
+    pub mint: Account<'info, Mint>,
+    #[account(seeds = [b"oracle", oracle.as_ref()], bump = oracle_profile.bump)]
+    pub oracle_profile: Option<Account<'info, OracleProfile>>,
+    #[account(mut, seeds = [b"permit", creator.key().as_ref()], bump = creator_permit.bump)]
+    pub creator_permit: Option<Account<'info, CreatorPermit>>,
+    #[account(mut)]
+    pub creator_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> CreateMarketAuto<'info> {
+    fn new_market(&mut self) -> NewMarket<'_, 'info> {
+        NewMarket {
+            market: &mut self.market,
+            market_vault: &self.market_vault,
+            bond_vault: &self.bond_vault,
+            global_state: &mut self.global_state,
+            mint: &self.mint,
+            oracle_profile: self.oracle_profile.as_ref(),
+            creator_permit: self.creator_permit.as_mut(),
+            creator_token_account: self.creator_token_account.as_ref(),
+            creator: &self.creator,
+            token_program: &self.token_program,
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(metadata_uri: String, category: MarketCategory, tags: Vec<String>)]
+pub struct UpdateMarketMetadata<'info> {
+    #[account(
+        mut,
+        has_one = creator,
+        realloc = Market::space(&market.question, &market.outcomes, &metadata_uri, &tags),
+        realloc::payer = creator,
+        realloc::zero = false,
+    )]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBet<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserBet::space(market.outcomes.len()),
+        seeds = [b"bet", user.key().as_ref(), market.key().as_ref()],
+        bump
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveMarket<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut, seeds = [b"oracle", resolver.key().as_ref()], bump = oracle_profile.bump)]
+    pub oracle_profile: Option<Account<'info, OracleProfile>>,
+    #[account(mut, seeds = [b"permit", market.creator.as_ref()], bump = creator_permit.bump)]
+    pub creator_permit: Option<Account<'info, CreatorPermit>>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    pub resolver: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimWinnings<'info> {
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        seeds = [b"bet", user.key().as_ref(), market.key().as_ref()],
+        bump = user_bet.bump
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimMany<'info> {
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = fee_token_account.owner == global_state.fee_recipient @ ErrorCode::InvalidFeeRecipient
+    )]
+    pub fee_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateOracle<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut, close = authority)]
+    pub pending_change: Account<'info, PendingChange>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PauseMarket<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    pub global_state: Account<'info, GlobalState>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnpauseMarket<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    pub global_state: Account<'info, GlobalState>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMarket<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut, seeds = [b"permit", market.creator.as_ref()], bump = creator_permit.bump)]
+    pub creator_permit: Option<Account<'info, CreatorPermit>>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferPosition<'info> {
+    #[account(
+        mut,
+        seeds = [b"bet", user.key().as_ref(), from_bet.market.as_ref()],
+        bump = from_bet.bump
+    )]
+    pub from_bet: Account<'info, UserBet>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserBet::space(from_bet.bets.len()),
+        seeds = [b"bet", recipient.key().as_ref(), from_bet.market.as_ref()],
+        bump
+    )]
+    pub to_bet: Account<'info, UserBet>,
+    /// CHECK: any wallet can receive a position
+    pub recipient: UncheckedAccount<'info>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct CreateOffer<'info> {
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Offer::INIT_SPACE,
+        seeds = [b"offer", seller.key().as_ref(), offer_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+    pub market: Account<'info, Market>,
+    #[account(seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bet", seller.key().as_ref(), market.key().as_ref()],
+        bump = seller_bet.bump
+    )]
+    pub seller_bet: Account<'info, UserBet>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FillOffer<'info> {
+    #[account(mut, has_one = seller, has_one = market, close = seller)]
+    pub offer: Account<'info, Offer>,
+    pub market: Account<'info, Market>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = UserBet::space(market.outcomes.len()),
+        seeds = [b"bet", buyer.key().as_ref(), market.key().as_ref()],
+        bump
+    )]
+    pub buyer_bet: Account<'info, UserBet>,
+    #[account(
+        mut,
+        seeds = [b"bet", seller.key().as_ref(), market.key().as_ref()],
+        bump = seller_bet.bump
+    )]
+    pub seller_bet: Account<'info, UserBet>,
+    #[account(mut, constraint = buyer_token_account.mint == offer.mint @ ErrorCode::InvalidMint)]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == offer.mint @ ErrorCode::InvalidMint,
+        constraint = seller_token_account.owner == seller.key() @ ErrorCode::Unauthorized
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    #[account(mut, has_one = seller, close = seller)]
+    pub offer: Account<'info, Offer>,
+    #[account(
+        mut,
+        seeds = [b"bet", seller.key().as_ref(), offer.market.as_ref()],
+        bump = seller_bet.bump
+    )]
+    pub seller_bet: Account<'info, UserBet>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBetWithReceipt<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = user,
+        mint::decimals = 0,
+        mint::authority = user
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = user
+    )]
+    pub receipt_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + PositionReceipt::INIT_SPACE,
+        seeds = [b"receipt", receipt_mint.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, PositionReceipt>,
+    /// CHECK: created by Token Metadata - the seeds tie it to the receipt mint
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata::ID.as_ref(), receipt_mint.key().as_ref()],
+        seeds::program = token_metadata::ID,
+        bump
+    )]
+    pub receipt_metadata: UncheckedAccount<'info>,
+    /// CHECK: created by Token Metadata - the seeds tie it to the receipt mint
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata::ID.as_ref(), receipt_mint.key().as_ref(), b"edition"],
+        seeds::program = token_metadata::ID,
+        bump
+    )]
+    pub receipt_edition: UncheckedAccount<'info>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: pinned to the Token Metadata program id
+    #[account(address = token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReceipt<'info> {
+    pub market: Account<'info, Market>,
+    #[account(mut, has_one = market, seeds = [b"receipt", receipt.mint.as_ref()], bump = receipt.bump)]
+    pub receipt: Account<'info, PositionReceipt>,
+    #[account(mut, address = receipt.mint)]
+    pub receipt_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        constraint = receipt_token_account.mint == receipt.mint @ ErrorCode::ReceiptNotHeld,
+        constraint = receipt_token_account.owner == holder.key() @ ErrorCode::ReceiptNotHeld,
+        constraint = receipt_token_account.amount == 1 @ ErrorCode::ReceiptNotHeld
+    )]
+    pub receipt_token_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct CreateDelegate<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Delegate::INIT_SPACE,
+        seeds = [b"delegate", user.key().as_ref(), session_key.as_ref()],
+        bump
+    )]
+    pub delegate: Account<'info, Delegate>,
+    /// CHECK: PDA the user's token account approves; only signs transfers
+    #[account(seeds = [b"spender", user.key().as_ref()], bump)]
+    pub spend_authority: UncheckedAccount<'info>,
+    #[account(mut, token::authority = user)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegate<'info> {
+    #[account(mut, has_one = user, close = user)]
+    pub delegate: Account<'info, Delegate>,
+    /// CHECK: PDA the user's token account approves; only signs transfers
+    #[account(seeds = [b"spender", user.key().as_ref()], bump)]
+    pub spend_authority: UncheckedAccount<'info>,
+    #[account(mut, token::authority = user)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBetDelegated<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        has_one = session_key,
+        seeds = [b"delegate", delegate.user.as_ref(), session_key.key().as_ref()],
+        bump = delegate.bump
+    )]
+    pub delegate: Account<'info, Delegate>,
+    #[account(
+        init_if_needed,
+        payer = session_key,
+        space = UserBet::space(market.outcomes.len()),
+        seeds = [b"bet", delegate.user.as_ref(), market.key().as_ref()],
+        bump
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    /// CHECK: PDA the user's token account approves; only signs transfers
+    #[account(seeds = [b"spender", delegate.user.as_ref()], bump)]
+    pub spend_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::authority = delegate.user)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRelayBudget<'info> {
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + RelayNonce::INIT_SPACE,
+        seeds = [b"nonce", user.key().as_ref()],
+        bump
+    )]
+    pub relay_nonce: Account<'info, RelayNonce>,
+    /// CHECK: PDA the user's token account approves; only signs transfers
+    #[account(seeds = [b"spender", user.key().as_ref()], bump)]
+    pub spend_authority: UncheckedAccount<'info>,
+    #[account(mut, token::authority = user)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    // A relayer can cover the rent for users who hold no SOL
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBetRelayed<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = UserBet::space(market.outcomes.len()),
+        seeds = [b"bet", user.key().as_ref(), market.key().as_ref()],
+        bump
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut, seeds = [b"nonce", user.key().as_ref()], bump = relay_nonce.bump)]
+    pub relay_nonce: Account<'info, RelayNonce>,
+    /// CHECK: PDA the user's token account approves; only signs transfers
+    #[account(seeds = [b"spender", user.key().as_ref()], bump)]
+    pub spend_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::authority = user)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = relayer_token_account.mint == user_token_account.mint @ ErrorCode::InvalidMint)]
+    pub relayer_token_account: Account<'info, TokenAccount>,
+    /// CHECK: proven by the Ed25519 signature over the intent
+    pub user: UncheckedAccount<'info>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+    /// CHECK: instructions sysvar
+    #[account(address = ix_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        seeds = [b"bet", user.key().as_ref(), market.key().as_ref()],
+        bump = user_bet.bump
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeJurorRegistry<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + JurorRegistry::INIT_SPACE,
+        seeds = [b"juror_registry"],
+        bump
+    )]
+    pub juror_registry: Account<'info, JurorRegistry>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"juror_vault"],
+        bump,
+        token::mint = mint,
+        token::authority = juror_vault,
+    )]
+    pub juror_vault: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterJuror<'info> {
+    #[account(mut, seeds = [b"juror_registry"], bump = juror_registry.bump)]
+    pub juror_registry: Account<'info, JurorRegistry>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Juror::INIT_SPACE,
+        seeds = [b"juror", authority.key().as_ref()],
+        bump
+    )]
+    pub juror: Account<'info, Juror>,
+    #[account(mut, seeds = [b"juror_vault"], bump)]
+    pub juror_vault: Account<'info, TokenAccount>,
+    #[account(mut, constraint = juror_token_account.mint == juror_registry.mint @ ErrorCode::InvalidMint)]
+    pub juror_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawJuror<'info> {
+    #[account(mut, seeds = [b"juror_registry"], bump = juror_registry.bump)]
+    pub juror_registry: Account<'info, JurorRegistry>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"juror", authority.key().as_ref()],
+        bump = juror.bump
+    )]
+    pub juror: Account<'info, Juror>,
+    #[account(mut, seeds = [b"juror_vault"], bump)]
+    pub juror_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub juror_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    #[account(
+        init,
+        payer = challenger,
+        space = 8 + Dispute::INIT_SPACE,
+        seeds = [b"dispute", market.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+    #[account(
+        seeds = [b"bet", challenger.key().as_ref(), market.key().as_ref()],
+        bump = user_bet.bump
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut, seeds = [b"juror_registry"], bump = juror_registry.bump)]
+    pub juror_registry: Account<'info, JurorRegistry>,
+    #[account(mut, seeds = [b"juror_vault"], bump)]
+    pub juror_vault: Account<'info, TokenAccount>,
+    #[account(mut, constraint = challenger_token_account.mint == juror_registry.mint @ ErrorCode::InvalidMint)]
+    pub challenger_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    /// CHECK: Read raw for panel entropy; address pinned to the SlotHashes sysvar
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitVote<'info> {
+    #[account(mut)]
+    pub dispute: Account<'info, Dispute>,
+    pub global_state: Account<'info, GlobalState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealVote<'info> {
+    #[account(mut, has_one = market)]
+    pub dispute: Account<'info, Dispute>,
+    pub market: Account<'info, Market>,
+    pub global_state: Account<'info, GlobalState>,
+    pub juror: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeDispute<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [b"dispute", market.key().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+    pub global_state: Account<'info, GlobalState>,
+    /// CHECK: the oracle's profile PDA - read and slashed in the handler only if the oracle has registered
+    #[account(mut, seeds = [b"oracle", market.oracle.as_ref()], bump)]
+    pub oracle_profile: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"oracle_vault", market.oracle.as_ref()], bump)]
+    pub oracle_vault: Option<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Option<Account<'info, TokenAccount>>,
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct SettleJuror<'info> {
+    #[account(mut, seeds = [b"juror_registry"], bump = juror_registry.bump)]
+    pub juror_registry: Account<'info, JurorRegistry>,
+    #[account(mut)]
+    pub dispute: Account<'info, Dispute>,
+    #[account(mut, seeds = [b"juror", juror.juror.as_ref()], bump = juror.bump)]
+    pub juror: Account<'info, Juror>,
+    #[account(mut, seeds = [b"juror_vault"], bump)]
+    pub juror_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = fee_token_account.owner == global_state.fee_recipient @ ErrorCode::InvalidFeeRecipient
+    )]
+    pub fee_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SettleDisputeBond<'info> {
+    #[account(mut)]
+    pub dispute: Account<'info, Dispute>,
+    #[account(mut, seeds = [b"juror_vault"], bump)]
+    pub juror_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterOracle<'info> {
+    #[account(
+        init,
+        payer = oracle,
+        space = 8 + OracleProfile::INIT_SPACE,
+        seeds = [b"oracle", oracle.key().as_ref()],
+        bump
+    )]
+    pub oracle_profile: Account<'info, OracleProfile>,
+    #[account(
+        init,
+        payer = oracle,
+        seeds = [b"oracle_vault", oracle.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = oracle_vault,
+    )]
+    pub oracle_vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub oracle_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AddOracleStake<'info> {
+    #[account(mut, seeds = [b"oracle", oracle.key().as_ref()], bump = oracle_profile.bump)]
+    pub oracle_profile: Account<'info, OracleProfile>,
+    #[account(mut, seeds = [b"oracle_vault", oracle.key().as_ref()], bump)]
+    pub oracle_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub oracle_token_account: Account<'info, TokenAccount>,
+    pub oracle: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RequestOracleUnstake<'info> {
+    #[account(mut, seeds = [b"oracle", oracle.key().as_ref()], bump = oracle_profile.bump)]
+    pub oracle_profile: Account<'info, OracleProfile>,
+    pub oracle: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawOracleStake<'info> {
+    #[account(mut, seeds = [b"oracle", oracle.key().as_ref()], bump = oracle_profile.bump)]
+    pub oracle_profile: Account<'info, OracleProfile>,
+    #[account(mut, seeds = [b"oracle_vault", oracle.key().as_ref()], bump)]
+    pub oracle_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub oracle_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    pub oracle: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimResolverFee<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub resolver_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    pub resolver: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimResolverBounty<'info> {
+    #[account(mut, has_one = creator)]
+    pub market: Account<'info, Market>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub creator_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    pub creator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetCreationBond<'info> {
+    #[account(mut)]
+    pub global_state: Account<'info, GlobalState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleCreationBond<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+    #[account(mut, seeds = [b"bond", market.key().as_ref()], bump)]
+    pub bond_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == market.creator @ ErrorCode::Unauthorized
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = treasury_token_account.owner == global_state.fee_recipient @ ErrorCode::InvalidFeeRecipient
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    /// CHECK: Receives the bond vault's rent; pinned to the market creator
+    #[account(mut, address = market.creator)]
+    pub creator: UncheckedAccount<'info>,
+    pub global_state: Account<'info, GlobalState>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetCreationMode<'info> {
+    #[account(mut)]
+    pub global_state: Account<'info, GlobalState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct IssuePermit<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + CreatorPermit::INIT_SPACE,
+        seeds = [b"permit", creator.as_ref()],
+        bump
+    )]
+    pub creator_permit: Account<'info, CreatorPermit>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLimits<'info> {
+    #[account(mut, seeds = [b"global"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGlobalPause<'info> {
+    #[account(mut, seeds = [b"global"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(grantee: Pubkey, role: Role)]
+pub struct GrantRole<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RoleGrant::INIT_SPACE,
+        seeds = [b"role", grantee.as_ref(), &[role as u8]],
+        bump
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(mut, close = authority)]
+    pub role_grant: Account<'info, RoleGrant>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokePermit<'info> {
+    #[account(
+        mut,
+        seeds = [b"permit", creator_permit.creator.as_ref()],
+        bump = creator_permit.bump
+    )]
+    pub creator_permit: Account<'info, CreatorPermit>,
+    pub global_state: Account<'info, GlobalState>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(mut, seeds = [b"global"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut, close = authority)]
+    pub pending_change: Account<'info, PendingChange>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(mut, seeds = [b"global"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds = [b"global"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut, close = authority)]
+    pub pending_change: Account<'info, PendingChange>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMaxFee<'info> {
+    #[account(mut, seeds = [b"global"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut, close = authority)]
+    pub pending_change: Account<'info, PendingChange>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueueChange<'info> {
+    #[account(mut, seeds = [b"global"], bump = global_state.bump)]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PendingChange::INIT_SPACE,
+        seeds = [b"change", global_state.change_nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelChange<'info> {
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut, close = authority)]
+    pub pending_change: Account<'info, PendingChange>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64, question: String, outcomes: Vec<String>)]
+pub struct CreateLargeMarket<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + LargeMarket::INIT_SPACE,
+        seeds = [b"large_market", market_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub market: AccountLoader<'info, LargeMarket>,
+    #[account(
+        init,
+        payer = creator,
+        space = OutcomeLabels::space(&question, &outcomes),
+        seeds = [b"labels", market.key().as_ref()],
+        bump
+    )]
+    pub outcome_labels: Account<'info, OutcomeLabels>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"vault", market.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = market_vault,
+    )]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub global_state: Account<'info, GlobalState>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceLargeBet<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, LargeMarket>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserBet::space(market.load()?.outcome_count as usize),
+        seeds = [b"bet", user.key().as_ref(), market.key().as_ref()],
+        bump
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveLargeMarket<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, LargeMarket>,
+    pub global_state: Account<'info, GlobalState>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    pub resolver: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PauseLargeMarket<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, LargeMarket>,
+    pub global_state: Account<'info, GlobalState>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseLargeMarket<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, LargeMarket>,
+    pub global_state: Account<'info, GlobalState>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimLargeWinnings<'info> {
+    pub market: AccountLoader<'info, LargeMarket>,
+    #[account(mut, has_one = market)]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
     pub market_vault: Account<'info, TokenAccount>,
     #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CollectLargeFees<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, LargeMarket>,
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = fee_token_account.owner == global_state.fee_recipient @ ErrorCode::InvalidFeeRecipient
+    )]
     pub fee_token_account: Account<'info, TokenAccount>,
     pub global_state: Account<'info, GlobalState>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
     pub authority: Signer<'info>,
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateOracle<'info> {
+pub struct MigrateAccount<'info> {
+    /// CHECK: may hold a legacy layout - owner checked here, discriminator on deserialize
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateMarket<'info> {
+    /// CHECK: may hold a legacy layout - owner checked here, discriminator on deserialize
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+    /// CHECK: may hold either GlobalState layout - only its fee is read
+    #[account(seeds = [b"global"], bump, owner = crate::ID)]
+    pub global_state: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(series_id: u64, question: String, outcomes: Vec<String>)]
+pub struct CreateSeries<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = MarketSeries::space(&question, &outcomes),
+        seeds = [b"series", creator.key().as_ref(), series_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub series: Account<'info, MarketSeries>,
+    pub global_state: Account<'info, GlobalState>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RollSeries<'info> {
+    #[account(
+        mut,
+        seeds = [b"series", series.creator.as_ref(), series.series_id.to_le_bytes().as_ref()],
+        bump = series.bump
+    )]
+    pub series: Account<'info, MarketSeries>,
+    #[account(
+        init,
+        payer = payer,
+        space = Market::space(&series.question, &series.outcomes, "", &[]),
+        seeds = [b"market", series.key().as_ref(), series.next_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"vault", market.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = market_vault,
+    )]
+    pub market_vault: Account<'info, TokenAccount>,
+    #[account(address = series.mint @ ErrorCode::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub global_state: Account<'info, GlobalState>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: u64, title: String, metadata_uri: String)]
+pub struct CreateEvent<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = MarketEvent::space(&title, &metadata_uri),
+        seeds = [b"event", creator.key().as_ref(), event_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub event: Account<'info, MarketEvent>,
+    pub global_state: Account<'info, GlobalState>,
     #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddEventMarket<'info> {
+    #[account(mut, has_one = creator)]
+    pub event: Account<'info, MarketEvent>,
+    #[account(mut, has_one = creator)]
     pub market: Account<'info, Market>,
+    // One event per market - init fails if the market already belongs to one
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + EventMembership::INIT_SPACE,
+        seeds = [b"event_member", market.key().as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, EventMembership>,
     pub global_state: Account<'info, GlobalState>,
-    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct PauseMarket<'info> {
+pub struct UpdateEvent<'info> {
     #[account(mut)]
-    pub market: Account<'info, Market>,
+    pub event: Account<'info, MarketEvent>,
     pub global_state: Account<'info, GlobalState>,
+    #[account(mut, seeds = [b"permit", event.creator.as_ref()], bump = creator_permit.bump)]
+    pub creator_permit: Option<Account<'info, CreatorPermit>>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
     pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct UnpauseMarket<'info> {
+#[instruction(group_id: u64)]
+pub struct CreateExclusiveGroup<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + ExclusiveGroup::INIT_SPACE,
+        seeds = [b"exclusive_group", creator.key().as_ref(), group_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub group: Account<'info, ExclusiveGroup>,
+    pub global_state: Account<'info, GlobalState>,
+    pub mint: Account<'info, Mint>,
     #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddGroupMarket<'info> {
+    #[account(mut, has_one = creator)]
+    pub group: Account<'info, ExclusiveGroup>,
+    #[account(mut, has_one = creator)]
     pub market: Account<'info, Market>,
+    #[account(seeds = [b"vault", market.key().as_ref()], bump)]
+    pub market_vault: Account<'info, TokenAccount>,
+    /// CHECK: must not exist - only its address is checked
+    #[account(seeds = [b"event_member", market.key().as_ref()], bump)]
+    pub event_membership: UncheckedAccount<'info>,
     pub global_state: Account<'info, GlobalState>,
-    pub authority: Signer<'info>,
+    pub creator: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct CloseMarket<'info> {
+pub struct ResolveGroup<'info> {
     #[account(mut)]
-    pub market: Account<'info, Market>,
+    pub group: Account<'info, ExclusiveGroup>,
     pub global_state: Account<'info, GlobalState>,
-    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"oracle", resolver.key().as_ref()], bump = oracle_profile.bump)]
+    pub oracle_profile: Option<Account<'info, OracleProfile>>,
+    // Members share the group's creator, so one permit frees every slot
+    #[account(mut, seeds = [b"permit", group.creator.as_ref()], bump = creator_permit.bump)]
+    pub creator_permit: Option<Account<'info, CreatorPermit>>,
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    pub resolver: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct ClaimRefund<'info> {
-    pub market: Account<'info, Market>,
+pub struct ConvertNoPosition<'info> {
+    pub group: Account<'info, ExclusiveGroup>,
     #[account(mut)]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        seeds = [b"bet", user.key().as_ref(), market.key().as_ref()],
+        bump = user_bet.bump
+    )]
     pub user_bet: Account<'info, UserBet>,
-    #[account(mut)]
+    #[account(mut, seeds = [b"vault", market.key().as_ref()], bump)]
     pub market_vault: Account<'info, TokenAccount>,
+    pub global_state: Account<'info, GlobalState>,
     #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
     pub user: Signer<'info>,
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 // Clean events
@@ -623,6 +5992,28 @@ pub struct PlatformInitialized {
 
 #[event]
 pub struct MarketCreated {
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub question: String,
+    pub outcomes: Vec<String>,
+    pub end_time: i64,
+    pub oracle: Pubkey,
+    pub metadata_uri: String,
+    pub category: MarketCategory,
+    pub tags: Vec<String>,
+    pub rules_hash: [u8; 32],
+}
+
+#[event]
+pub struct MarketMetadataUpdated {
+    pub market: Pubkey,
+    pub metadata_uri: String,
+    pub category: MarketCategory,
+    pub tags: Vec<String>,
+}
+
+#[event]
+pub struct LargeMarketCreated {
     pub market_id: u64,
     pub question: String,
     pub outcomes: Vec<String>,
@@ -630,9 +6021,87 @@ pub struct MarketCreated {
     pub oracle: Pubkey,
 }
 
+#[event]
+pub struct SeriesCreated {
+    pub series: Pubkey,
+    pub series_id: u64,
+    pub creator: Pubkey,
+    pub duration: i64,
+}
+
+#[event]
+pub struct SeriesRolled {
+    pub series: Pubkey,
+    pub market: Pubkey,
+    pub index: u64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct EventCreated {
+    pub event: Pubkey,
+    pub event_id: u64,
+    pub title: String,
+    pub end_time: i64,
+    pub oracle: Pubkey,
+}
+
+#[event]
+pub struct EventMarketAdded {
+    pub event: Pubkey,
+    pub market: Pubkey,
+}
+
+#[event]
+pub struct EventPauseUpdated {
+    pub event: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct EventClosed {
+    pub event: Pubkey,
+    pub bonds_forfeited: bool,
+}
+
+#[event]
+pub struct EventRetimed {
+    pub event: Pubkey,
+    pub old_end_time: i64,
+    pub new_end_time: i64,
+}
+
+#[event]
+pub struct ExclusiveGroupCreated {
+    pub group: Pubkey,
+    pub group_id: u64,
+    pub oracle: Pubkey,
+}
+
+#[event]
+pub struct GroupMarketAdded {
+    pub group: Pubkey,
+    pub market: Pubkey,
+}
+
+#[event]
+pub struct GroupResolved {
+    pub group: Pubkey,
+    pub winning_index: u8,
+}
+
+#[event]
+pub struct NoPositionConverted {
+    pub group: Pubkey,
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
 #[event]
 pub struct BetPlaced {
     pub user: Pubkey,
+    pub market: Pubkey,
     pub market_id: u64,
     pub outcome_index: u8,
     pub amount: u64,
@@ -640,6 +6109,7 @@ pub struct BetPlaced {
 
 #[event]
 pub struct MarketResolved {
+    pub market: Pubkey,
     pub market_id: u64,
     pub winner: u8,
 }
@@ -659,9 +6129,82 @@ pub struct FeesCollected {
 
 #[event]
 pub struct MarketClosed {
+    pub market: Pubkey,
     pub market_id: u64,
 }
 
+#[event]
+pub struct PositionTransferred {
+    pub market: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct OfferCreated {
+    pub offer: Pubkey,
+    pub market: Pubkey,
+    pub seller: Pubkey,
+    pub outcome_index: u8,
+    pub amount: u64,
+    pub price: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct OfferFilled {
+    pub offer: Pubkey,
+    pub market: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+}
+
+#[event]
+pub struct OfferCancelled {
+    pub offer: Pubkey,
+    pub seller: Pubkey,
+}
+
+#[event]
+pub struct ReceiptMinted {
+    pub market: Pubkey,
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub outcome_index: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReceiptClaimed {
+    pub market: Pubkey,
+    pub mint: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DelegateCreated {
+    pub user: Pubkey,
+    pub session_key: Pubkey,
+    pub expires_at: i64,
+    pub spending_cap: u64,
+}
+
+#[event]
+pub struct DelegateRevoked {
+    pub user: Pubkey,
+    pub session_key: Pubkey,
+}
+
+#[event]
+pub struct RelayBudgetSet {
+    pub user: Pubkey,
+    pub spending_cap: u64,
+}
+
 #[event]
 pub struct RefundClaimed {
     pub user: Pubkey,
@@ -686,6 +6229,199 @@ pub struct MarketUnpaused {
     pub market_id: u64,
 }
 
+#[event]
+pub struct JurorRegistryInitialized {
+    pub mint: Pubkey,
+    pub min_stake: u64,
+    pub slash_bps: u16,
+}
+
+#[event]
+pub struct JurorRegistered {
+    pub juror: Pubkey,
+    pub stake: u64,
+}
+
+#[event]
+pub struct JurorWithdrawn {
+    pub juror: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DisputeBondSettled {
+    pub market: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub forfeited: bool,
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub market_id: u64,
+    pub challenger: Pubkey,
+    pub panel: Vec<Pubkey>,
+}
+
+#[event]
+pub struct VoteCommitted {
+    pub market: Pubkey,
+    pub juror: Pubkey,
+}
+
+#[event]
+pub struct VoteRevealed {
+    pub market: Pubkey,
+    pub juror: Pubkey,
+    pub outcome: u8,
+}
+
+#[event]
+pub struct DisputeFinalized {
+    pub market_id: u64,
+    pub original_winner: u8,
+    pub outcome: u8,
+    pub overturned: bool,
+}
+
+#[event]
+pub struct OracleRegistered {
+    pub oracle: Pubkey,
+    pub mint: Pubkey,
+    pub stake: u64,
+}
+
+#[event]
+pub struct OracleStakeChanged {
+    pub oracle: Pubkey,
+    pub stake: u64,
+}
+
+#[event]
+pub struct OracleUnstakeRequested {
+    pub oracle: Pubkey,
+    pub available_at: i64,
+}
+
+#[event]
+pub struct OracleSlashed {
+    pub oracle: Pubkey,
+    pub market_id: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ResolverPaid {
+    pub market_id: u64,
+    pub resolver: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BountyReclaimed {
+    pub market_id: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CreationBondUpdated {
+    pub amount: u64,
+}
+
+#[event]
+pub struct CreationBondSettled {
+    pub market_id: u64,
+    pub amount: u64,
+    pub forfeited: bool,
+}
+
+#[event]
+pub struct CreationModeUpdated {
+    pub permissioned: bool,
+}
+
+#[event]
+pub struct PermitIssued {
+    pub creator: Pubkey,
+    pub max_open_markets: u32,
+    pub max_duration: i64,
+}
+
+#[event]
+pub struct PermitRevoked {
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct FeeUpdated {
+    pub old_fee_bps: u16,
+    pub new_fee_bps: u16,
+}
+
+#[event]
+pub struct FeeRecipientUpdated {
+    pub old_fee_recipient: Pubkey,
+    pub new_fee_recipient: Pubkey,
+}
+
+#[event]
+pub struct ChangeQueued {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ChangeCancelled {
+    pub id: u64,
+}
+
+#[event]
+pub struct LimitsUpdated {
+    pub limits: PlatformLimits,
+}
+
+#[event]
+pub struct DisputeWindowUpdated {
+    pub dispute_window: i64,
+}
+
+#[event]
+pub struct GlobalPauseUpdated {
+    pub paused_ops: u8,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub grantee: Pubkey,
+    pub role: Role,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub grantee: Pubkey,
+    pub role: Role,
+}
+
+#[event]
+pub struct JurorSlashed {
+    pub juror: Pubkey,
+    pub market: Pubkey,
+    pub amount: u64,
+}
+
 
 #[error_code]
 pub enum ErrorCode {
@@ -747,4 +6483,168 @@ pub enum ErrorCode {
     AlreadyPaused,
     #[msg("Market not paused")]
     MarketNotPaused,
+    #[msg("Market is under dispute")]
+    MarketDisputed,
+    #[msg("Dispute window still open")]
+    DisputeWindowOpen,
+    #[msg("Dispute window closed")]
+    DisputeWindowClosed,
+    #[msg("No position in this market")]
+    NoPosition,
+    #[msg("Stake below minimum")]
+    StakeTooLow,
+    #[msg("Invalid slash rate")]
+    InvalidSlashBps,
+    #[msg("Invalid period")]
+    InvalidPeriod,
+    #[msg("Juror registry full")]
+    RegistryFull,
+    #[msg("Not enough jurors for a panel")]
+    NotEnoughJurors,
+    #[msg("Juror has unsettled disputes")]
+    JurorBusy,
+    #[msg("Invalid slot hashes sysvar")]
+    InvalidSlotHashes,
+    #[msg("Invalid mint")]
+    InvalidMint,
+    #[msg("Not on the juror panel")]
+    NotOnPanel,
+    #[msg("Commit phase over")]
+    CommitPhaseOver,
+    #[msg("Commit phase still active")]
+    CommitPhaseActive,
+    #[msg("Reveal phase over")]
+    RevealPhaseOver,
+    #[msg("Reveal phase still active")]
+    RevealPhaseActive,
+    #[msg("Invalid commitment")]
+    InvalidCommitment,
+    #[msg("Vote already committed")]
+    AlreadyCommitted,
+    #[msg("No vote committed")]
+    NotCommitted,
+    #[msg("Vote already revealed")]
+    AlreadyRevealed,
+    #[msg("Reveal does not match commitment")]
+    InvalidReveal,
+    #[msg("Dispute not finalized")]
+    DisputeNotFinalized,
+    #[msg("Dispute already finalized")]
+    DisputeFinalized,
+    #[msg("Juror already settled")]
+    AlreadySettled,
+    #[msg("Oracle not registered")]
+    OracleNotRegistered,
+    #[msg("Oracle stake below market minimum")]
+    OracleStakeTooLow,
+    #[msg("Oracle is unbonding")]
+    OracleUnbonding,
+    #[msg("Unstake not requested")]
+    UnstakeNotRequested,
+    #[msg("Unstake cooldown active")]
+    CooldownActive,
+    #[msg("Insufficient stake")]
+    InsufficientStake,
+    #[msg("Missing accounts to slash oracle")]
+    MissingSlashAccounts,
+    #[msg("Creator token account required")]
+    MissingCreatorTokenAccount,
+    #[msg("Creator not permitted")]
+    CreatorNotPermitted,
+    #[msg("Mint not allowed for creator")]
+    MintNotAllowed,
+    #[msg("Duration not allowed for creator")]
+    DurationNotAllowed,
+    #[msg("Creator open market limit reached")]
+    PermitMarketLimit,
+    #[msg("Creator permit required to free the market's slot")]
+    PermitRequired,
+    #[msg("Event has already ended")]
+    EventEnded,
+    #[msg("Group needs another market to convert into")]
+    GroupTooSmall,
+    #[msg("Too many mints")]
+    TooManyMints,
+    #[msg("Invalid metadata URI")]
+    InvalidMetadataUri,
+    #[msg("Invalid tags")]
+    InvalidTags,
+    #[msg("Invalid authority")]
+    InvalidAuthority,
+    #[msg("No pending authority")]
+    NoPendingAuthority,
+    #[msg("Queued change does not match")]
+    ChangeMismatch,
+    #[msg("Timelock delay not elapsed")]
+    TimelockActive,
+    #[msg("Operation paused platform-wide")]
+    PlatformPaused,
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+    #[msg("Limits outside absolute bounds")]
+    InvalidLimits,
+    #[msg("Global state account is malformed")]
+    InvalidGlobalState,
+    #[msg("Fee cap changes go through the timelock")]
+    FeeCapTimelocked,
+    #[msg("Fees already collected")]
+    FeesAlreadyCollected,
+    #[msg("Account already on the current version")]
+    AlreadyMigrated,
+    #[msg("Invalid series duration")]
+    InvalidDuration,
+    #[msg("Current series market has not ended")]
+    SeriesNotDue,
+    #[msg("Event is closed")]
+    EventClosed,
+    #[msg("Event has no room for more markets")]
+    EventFull,
+    #[msg("Market end time or oracle differs from the event")]
+    EventMismatch,
+    #[msg("Pass every event market, in order")]
+    EventMarketsMismatch,
+    #[msg("Market is settled through its group")]
+    MarketInGroup,
+    #[msg("Market belongs to an event")]
+    MarketInEvent,
+    #[msg("Group already resolved")]
+    GroupResolved,
+    #[msg("Group has no room for more markets")]
+    GroupFull,
+    #[msg("Pass every other group market with its vault and user bet, in order")]
+    GroupMarketsMismatch,
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Position smaller than amount")]
+    InsufficientPosition,
+    #[msg("Offer has expired")]
+    OfferExpired,
+    #[msg("Cancel open offers on this position first")]
+    OffersOpen,
+    #[msg("Invalid market vault")]
+    InvalidVault,
+    #[msg("Invalid user bet account")]
+    InvalidUserBet,
+    #[msg("Cannot transfer to yourself")]
+    InvalidRecipient,
+    #[msg("Signer does not hold the receipt")]
+    ReceiptNotHeld,
+    #[msg("Too many markets")]
+    TooManyMarkets,
+    #[msg("Session key expired")]
+    DelegateExpired,
+    #[msg("Session key may not bet on this market")]
+    MarketNotAllowed,
+    #[msg("Session key spending cap exceeded")]
+    SpendingCapExceeded,
+    #[msg("Missing or invalid Ed25519 signature")]
+    InvalidSignature,
+    #[msg("Bet intent expired")]
+    IntentExpired,
+    #[msg("Intent nonce already used")]
+    NonceUsed,
+    #[msg("Need between one leg and one leg per outcome")]
+    InvalidLegs,
+    #[msg("Pass (market, user_bet, market_vault) for each market")]
+    InvalidClaimAccounts,
 } 
\ No newline at end of file
@@ -94,7 +94,7 @@ async function deploy() {
     );
 
     const createMarketSignature = await program.methods
-      .createMarket(marketId, question, outcomes, endTime, oracle.publicKey, minBet)
+      .createMarket(marketId, question, outcomes, endTime, oracle.publicKey, minBet, new anchor.BN(0), 0, new anchor.BN(0), "", { crypto: {} }, ["btc"], Array(32).fill(0))
       .accounts({
         market,
         marketVault,